@@ -0,0 +1,139 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use omlc_core::{order, Aggregator, Dimension, PostWithScores};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+/// Board column, as given to `--sort`: `title`, `author`, `sum-avg`,
+/// `sum-var`, or a dimension key followed by `-avg` or `-var`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+  Title,
+  Author,
+  Avg(String),
+  Var(String),
+  SumAvg,
+  SumVar,
+}
+
+impl FromStr for Column {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(match s {
+      "title" => Column::Title,
+      "author" => Column::Author,
+      "sum-avg" => Column::SumAvg,
+      "sum-var" => Column::SumVar,
+      _ => {
+        if let Some(key) = s.strip_suffix("-avg") {
+          Column::Avg(key.to_string())
+        } else if let Some(key) = s.strip_suffix("-var") {
+          Column::Var(key.to_string())
+        } else {
+          return Err(format!("unknown column `{}`", s));
+        }
+      }
+    })
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Row {
+  pub title: String,
+  pub author: String,
+  pub url: String,
+  /// Aggregate and variance per dimension.
+  pub values: Vec<(f64, f64)>,
+  pub sum_avg: f64,
+  pub sum_var: f64,
+}
+
+impl Row {
+  pub fn new(p: &PostWithScores, dimensions: &[Dimension], aggregator: Aggregator) -> Self {
+    Row {
+      title: p.post.title.clone(),
+      author: p.post.author.clone(),
+      url: p.post.url.clone(),
+      values: (0..dimensions.len())
+        .map(|i| (p.agg(i, aggregator), p.var(i)))
+        .collect(),
+      sum_avg: p.sum_agg(dimensions, aggregator),
+      sum_var: p.sum_var(dimensions),
+    }
+  }
+
+  /// Every numeric cell, in the order of [`header`].
+  pub fn numbers(&self) -> Vec<f64> {
+    let mut res: Vec<_> = self.values.iter().flat_map(|&(a, v)| [a, v]).collect();
+    res.extend([self.sum_avg, self.sum_var]);
+    res
+  }
+
+  pub fn to_json<'a>(&'a self, dimensions: &'a [Dimension]) -> impl Serialize + 'a {
+    JsonRow {
+      row: self,
+      dimensions,
+    }
+  }
+}
+
+/// A row keyed by `<dimension>_avg` and `<dimension>_var`, in board order.
+struct JsonRow<'a> {
+  row: &'a Row,
+  dimensions: &'a [Dimension],
+}
+
+impl Serialize for JsonRow<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let row = self.row;
+    let mut map = serializer.serialize_map(None)?;
+    map.serialize_entry("title", &row.title)?;
+    map.serialize_entry("author", &row.author)?;
+    map.serialize_entry("url", &row.url)?;
+    for (d, (avg, var)) in self.dimensions.iter().zip(&row.values) {
+      map.serialize_entry(&format!("{}_avg", d.key), avg)?;
+      map.serialize_entry(&format!("{}_var", d.key), var)?;
+    }
+    map.serialize_entry("sum_avg", &row.sum_avg)?;
+    map.serialize_entry("sum_var", &row.sum_var)?;
+    map.end()
+  }
+}
+
+pub fn header(dimensions: &[Dimension]) -> Vec<String> {
+  let mut res = vec!["Title".to_string(), "Author".to_string()];
+  for d in dimensions {
+    res.push(format!("{} Avg", d.label));
+    res.push(format!("{} Var", d.label));
+  }
+  res.push("Σ Avg".to_string());
+  res.push("Σ Var".to_string());
+  res
+}
+
+pub fn sort(rows: &mut [Row], column: &Column, asc: bool, dimensions: &[Dimension]) -> Result<()> {
+  let dim = |key: &str| match dimensions.iter().position(|d| d.key == key) {
+    Some(i) => Ok(i),
+    None => bail!("unknown dimension `{}`", key),
+  };
+
+  // Unscored posts have NaN aggregates and stay at the bottom.
+  let cmp = |a: f64, b: f64| order(a, b, asc);
+  let text = |a: &str, b: &str| if asc { a.cmp(b) } else { b.cmp(a) };
+  match column {
+    Column::Title => rows.sort_by(|a, b| text(&a.title, &b.title)),
+    Column::Author => rows.sort_by(|a, b| text(&a.author, &b.author)),
+    Column::Avg(key) => {
+      let i = dim(key)?;
+      rows.sort_by(|a, b| cmp(a.values[i].0, b.values[i].0));
+    }
+    Column::Var(key) => {
+      let i = dim(key)?;
+      rows.sort_by(|a, b| cmp(a.values[i].1, b.values[i].1));
+    }
+    Column::SumAvg => rows.sort_by(|a, b| cmp(a.sum_avg, b.sum_avg)),
+    Column::SumVar => rows.sort_by(|a, b| cmp(a.sum_var, b.sum_var)),
+  }
+  Ok(())
+}
@@ -0,0 +1,238 @@
+mod board;
+mod output;
+
+use std::{
+  fs::File,
+  io::{self, Write},
+  path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use omlc_core::{
+  aggregate, audit_judge_csv, load_judge_csv, load_posts, normalize, validate_judge_csv,
+  Aggregator, Config, Method, Post, Score,
+};
+
+use crate::{
+  board::{Column, Row},
+  output::Format,
+};
+
+/// Computes the Open Mozheng Literature Cup leaderboard.
+#[derive(Debug, Parser)]
+#[command(name = "omlc", version)]
+struct Args {
+  /// Contest directory, holding `posts.json`, `scores/` and optionally
+  /// `contest.json`.
+  #[arg(long, default_value = ".")]
+  contest: PathBuf,
+
+  /// List of submissions [default: <contest>/posts.json].
+  #[arg(long)]
+  posts: Option<PathBuf>,
+
+  /// Directory holding one `<judge>.csv` per judge [default: <contest>/scores].
+  #[arg(long)]
+  scores: Option<PathBuf>,
+
+  /// Contest config (dimensions, column aliases, normalization) as JSON
+  /// [default: <contest>/contest.json if present, otherwise the Open Mozheng
+  /// Literature Cup's].
+  #[arg(long)]
+  config: Option<PathBuf>,
+
+  /// Normalization method, overriding the one in `--config`.
+  #[arg(long, value_parser = parse_method)]
+  normalizer: Option<Method>,
+
+  /// How each post's scores are combined in the `avg` columns: `mean`,
+  /// `median`, `trimmed-<k>` (dropping the k lowest and highest) or
+  /// `winsorized-<k>`.
+  #[arg(long, default_value = "mean")]
+  aggregator: Aggregator,
+
+  /// Column to sort the board by: `title`, `author`, `<dimension>-avg`,
+  /// `<dimension>-var`, `sum-avg` or `sum-var`.
+  #[arg(long, default_value = "sum-avg")]
+  sort: Column,
+
+  /// Sort ascending instead of descending.
+  #[arg(long)]
+  asc: bool,
+
+  /// Skip invalid rows, reporting them on stderr, instead of failing.
+  #[arg(long)]
+  skip_invalid: bool,
+
+  /// Output format.
+  #[arg(long, value_enum, default_value_t = Format::Table)]
+  format: Format,
+
+  /// Instead of the board, check the columns judges' spreadsheets derive
+  /// from their raw scores (such as `文采` or `总分`) against ours, failing if
+  /// any disagree. Rows the loader rejects are left out.
+  #[arg(long)]
+  audit: bool,
+}
+
+fn parse_method(name: &str) -> Result<Method, String> {
+  Method::from_name(name).ok_or_else(|| {
+    let names: Vec<_> = Method::ALL.iter().map(|m| m.name()).collect();
+    format!("expected one of {}", names.join(", "))
+  })
+}
+
+/// Every judge's CSV in `dir` with the judge's name, sorted by path.
+fn judge_files(dir: &Path) -> Result<Vec<(PathBuf, String)>> {
+  let mut paths = Vec::new();
+  for entry in std::fs::read_dir(dir).with_context(|| format!("cannot read `{}`", dir.display()))? {
+    let path = entry?.path();
+    if path.extension().is_some_and(|ext| ext == "csv") {
+      paths.push(path);
+    }
+  }
+  paths.sort();
+
+  paths
+    .into_iter()
+    .map(|path| {
+      let owner = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .with_context(|| format!("invalid judge file name `{}`", path.display()))?
+        .to_string();
+      Ok((path, owner))
+    })
+    .collect()
+}
+
+fn load_scores(args: &Args, dir: &Path, posts: &[Post], config: &Config) -> Result<Vec<Score>> {
+  let mut res = Vec::new();
+  for (path, owner) in judge_files(dir)? {
+    let owner = owner.as_str();
+
+    let file = File::open(&path).with_context(|| format!("cannot open `{}`", path.display()))?;
+    let mut scores = if args.skip_invalid {
+      let (scores, diagnostics) =
+        validate_judge_csv(&path.display().to_string(), owner, file, posts, config);
+      for d in diagnostics {
+        eprintln!("warning: {}", d);
+      }
+      scores
+    } else {
+      load_judge_csv(owner, file, posts, config)
+        .with_context(|| format!("in `{}`", path.display()))?
+    };
+
+    if let Err(e) = normalize(&mut scores, config) {
+      if !args.skip_invalid {
+        return Err(e.context(format!("in `{}`", path.display())));
+      }
+      eprintln!("warning: {}: skipped: {}", path.display(), e);
+      continue;
+    }
+    res.extend(scores);
+  }
+
+  Ok(res)
+}
+
+/// Prints the audit of every judge's derived columns, failing if any
+/// disagree.
+fn audit(dir: &Path, posts: &[Post], config: &Config, mut out: impl Write) -> Result<()> {
+  let dimensions = &config.dimensions;
+  let mut findings = 0;
+  let mut mismatches = 0;
+  for (path, owner) in judge_files(dir)? {
+    let contents =
+      std::fs::read(&path).with_context(|| format!("cannot open `{}`", path.display()))?;
+    let file = path.display().to_string();
+
+    let (mut scores, _) = validate_judge_csv(&file, &owner, contents.as_slice(), posts, config);
+    if let Err(e) = normalize(&mut scores, config) {
+      writeln!(out, "{}: skipped: {}", file, e)?;
+      continue;
+    }
+    let audit = audit_judge_csv(&owner, contents.as_slice(), posts, &scores, config)
+      .with_context(|| format!("in `{}`", file))?;
+
+    if let Some(formula) = audit.total_formula(dimensions) {
+      write!(out, "{}: total = {}", file, formula)?;
+      if let Some(rho) = audit.total_correlation {
+        write!(out, ", Spearman {:.3} with our Σ", rho)?;
+      }
+      writeln!(out)?;
+    }
+    if let Some(weights) = audit.describe_weights(dimensions) {
+      writeln!(out, "{}: total {}", file, weights)?;
+      mismatches += 1;
+    }
+    for finding in &audit.findings {
+      writeln!(
+        out,
+        "{}:{}: {} of `{}`: {}",
+        file,
+        finding.line,
+        finding.column.name(dimensions),
+        posts[finding.post_id].title,
+        finding.describe()
+      )?;
+    }
+    findings += audit.findings.len();
+  }
+
+  if findings > 0 || mismatches > 0 {
+    bail!(
+      "{} derived values disagree with the raw scores, {} judges' totals with the weights",
+      findings,
+      mismatches
+    );
+  }
+  Ok(())
+}
+
+fn main() -> Result<()> {
+  let args = Args::parse();
+  let config_path = args.config.clone().or_else(|| {
+    let path = args.contest.join("contest.json");
+    path.exists().then_some(path)
+  });
+  let mut config = match &config_path {
+    Some(path) => {
+      let file = File::open(path).with_context(|| format!("cannot open `{}`", path.display()))?;
+      serde_json::from_reader(file).with_context(|| format!("in `{}`", path.display()))?
+    }
+    None => Config::default(),
+  };
+  if let Some(method) = args.normalizer {
+    config.normalizer = method;
+  }
+
+  let posts_path = args
+    .posts
+    .clone()
+    .unwrap_or_else(|| args.contest.join("posts.json"));
+  let scores_dir = args
+    .scores
+    .clone()
+    .unwrap_or_else(|| args.contest.join("scores"));
+
+  let file =
+    File::open(&posts_path).with_context(|| format!("cannot open `{}`", posts_path.display()))?;
+  let posts = load_posts(file).with_context(|| format!("in `{}`", posts_path.display()))?;
+
+  if args.audit {
+    return audit(&scores_dir, &posts, &config, io::stdout().lock());
+  }
+  let scores = load_scores(&args, &scores_dir, &posts, &config)?;
+
+  let dimensions = &config.dimensions;
+  let mut rows: Vec<_> = aggregate(&posts, &scores)?
+    .iter()
+    .map(|p| Row::new(p, dimensions, args.aggregator))
+    .collect();
+  board::sort(&mut rows, &args.sort, args.asc, dimensions)?;
+
+  args.format.write(&rows, dimensions, io::stdout().lock())
+}
@@ -0,0 +1,107 @@
+use std::io::Write;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use omlc_core::Dimension;
+use unicode_width::UnicodeWidthStr;
+
+use crate::board::{header, Row};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+  Table,
+  Csv,
+  Json,
+  Markdown,
+}
+
+impl Format {
+  pub fn write(self, rows: &[Row], dimensions: &[Dimension], out: impl Write) -> Result<()> {
+    match self {
+      Format::Table => write_table(rows, dimensions, out),
+      Format::Csv => write_csv(rows, dimensions, out),
+      Format::Json => write_json(rows, dimensions, out),
+      Format::Markdown => write_markdown(rows, dimensions, out),
+    }
+  }
+}
+
+fn cells(row: &Row) -> Vec<String> {
+  let mut res = vec![row.title.clone(), row.author.clone()];
+  res.extend(row.numbers().iter().map(|v| format!("{:.3}", v)));
+  res
+}
+
+fn write_table(rows: &[Row], dimensions: &[Dimension], mut out: impl Write) -> Result<()> {
+  let header = header(dimensions);
+  let body: Vec<_> = rows.iter().map(cells).collect();
+
+  let mut widths: Vec<_> = header.iter().map(|h| h.width()).collect();
+  for cells in &body {
+    for (w, c) in widths.iter_mut().zip(cells) {
+      *w = (*w).max(c.width());
+    }
+  }
+
+  let line = |out: &mut dyn Write, cells: &[String]| -> Result<()> {
+    for (i, (c, w)) in cells.iter().zip(&widths).enumerate() {
+      let pad = " ".repeat(w - c.width());
+      if i > 0 {
+        write!(out, "  ")?;
+      }
+      // Text columns are left-aligned, numbers right-aligned.
+      if i < 2 {
+        write!(out, "{}{}", c, pad)?;
+      } else {
+        write!(out, "{}{}", pad, c)?;
+      }
+    }
+    writeln!(out)?;
+    Ok(())
+  };
+
+  line(&mut out, &header)?;
+  for cells in &body {
+    line(&mut out, cells)?;
+  }
+
+  Ok(())
+}
+
+fn write_csv(rows: &[Row], dimensions: &[Dimension], out: impl Write) -> Result<()> {
+  let mut writer = csv::Writer::from_writer(out);
+  writer.write_record(header(dimensions))?;
+  for row in rows {
+    let mut record = vec![row.title.clone(), row.author.clone()];
+    record.extend(row.numbers().iter().map(|v| v.to_string()));
+    writer.write_record(record)?;
+  }
+  writer.flush()?;
+  Ok(())
+}
+
+fn write_json(rows: &[Row], dimensions: &[Dimension], mut out: impl Write) -> Result<()> {
+  let rows: Vec<_> = rows.iter().map(|r| r.to_json(dimensions)).collect();
+  serde_json::to_writer_pretty(&mut out, &rows)?;
+  writeln!(out)?;
+  Ok(())
+}
+
+fn write_markdown(rows: &[Row], dimensions: &[Dimension], mut out: impl Write) -> Result<()> {
+  fn escape(s: &str) -> String {
+    s.replace('|', "\\|")
+  }
+
+  let header = header(dimensions);
+  writeln!(out, "| {} |", header.join(" | "))?;
+  writeln!(out, "|:--|:--|{}", "--:|".repeat(header.len() - 2))?;
+  for row in rows {
+    let mut cells = vec![
+      format!("[{}]({})", escape(&row.title), row.url),
+      escape(&row.author),
+    ];
+    cells.extend(row.numbers().iter().map(|v| format!("{:.3}", v)));
+    writeln!(out, "| {} |", cells.join(" | "))?;
+  }
+  Ok(())
+}
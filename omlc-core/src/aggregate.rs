@@ -0,0 +1,225 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::model::{mean, median, Post, PostWithScores, Score};
+
+/// Groups normalized scores by the post they belong to, keeping the order of
+/// `posts`.
+pub fn aggregate(posts: &[Post], scores: &[Score]) -> Result<Vec<PostWithScores>> {
+  let mut res: Vec<_> = posts
+    .iter()
+    .cloned()
+    .enumerate()
+    .map(|(id, post)| PostWithScores {
+      id,
+      post,
+      scores: Vec::new(),
+    })
+    .collect();
+
+  for score in scores {
+    let Some(entry) = res.get_mut(score.post_id) else {
+      bail!(
+        "score by `{}` refers to unknown post #{}",
+        score.owner,
+        score.post_id
+      );
+    };
+    entry.scores.push(score.clone());
+  }
+
+  Ok(res)
+}
+
+/// Competition ranks ("1224") of `values`, the highest value ranking first.
+/// NaN, e.g. the mean of a post nobody scored, ranks last.
+pub fn ranks(values: &[f64]) -> Vec<usize> {
+  values
+    .iter()
+    .map(|&v| {
+      1 + values
+        .iter()
+        .filter(|&&w| order(w, v, false).is_lt())
+        .count()
+    })
+    .collect()
+}
+
+/// Orders two values ascending or descending, NaN last either way.
+pub fn order(a: f64, b: f64, asc: bool) -> Ordering {
+  match (a.is_nan(), b.is_nan()) {
+    (false, false) if asc => a.total_cmp(&b),
+    (false, false) => b.total_cmp(&a),
+    (a, b) => a.cmp(&b),
+  }
+}
+
+/// How the judges' scores of a post are combined into one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Aggregator {
+  #[default]
+  Mean,
+  Median,
+  /// Mean after dropping the `k` lowest and `k` highest scores.
+  Trimmed(usize),
+  /// Mean after replacing the `k` lowest and `k` highest scores by the
+  /// nearest remaining ones.
+  Winsorized(usize),
+}
+
+impl Aggregator {
+  /// The aggregators offered by default.
+  pub const COMMON: [Aggregator; 6] = [
+    Aggregator::Mean,
+    Aggregator::Median,
+    Aggregator::Trimmed(1),
+    Aggregator::Trimmed(2),
+    Aggregator::Winsorized(1),
+    Aggregator::Winsorized(2),
+  ];
+
+  /// Combines `values`. Trimming or winsorizing all of them falls back to the
+  /// median.
+  pub fn apply(&self, values: &[f64]) -> f64 {
+    let (k, winsorize) = match *self {
+      Aggregator::Mean => return mean(values.iter().copied()),
+      Aggregator::Median => return median(values),
+      Aggregator::Trimmed(k) => (k, false),
+      Aggregator::Winsorized(k) => (k, true),
+    };
+
+    let n = values.len();
+    if 2 * k >= n {
+      return median(values);
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    if winsorize {
+      let (low, high) = (sorted[k], sorted[n - k - 1]);
+      mean(sorted.into_iter().map(|v| v.clamp(low, high)))
+    } else {
+      mean(sorted[k..n - k].iter().copied())
+    }
+  }
+
+  /// Short name used in column headers.
+  pub fn label(&self) -> String {
+    match self {
+      Aggregator::Mean => "Avg".to_string(),
+      Aggregator::Median => "Median".to_string(),
+      Aggregator::Trimmed(k) => format!("Trim{} Avg", k),
+      Aggregator::Winsorized(k) => format!("Wins{} Avg", k),
+    }
+  }
+}
+
+impl fmt::Display for Aggregator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Aggregator::Mean => write!(f, "mean"),
+      Aggregator::Median => write!(f, "median"),
+      Aggregator::Trimmed(k) => write!(f, "trimmed-{}", k),
+      Aggregator::Winsorized(k) => write!(f, "winsorized-{}", k),
+    }
+  }
+}
+
+/// Parses `mean`, `median`, `trimmed-<k>` or `winsorized-<k>`.
+impl FromStr for Aggregator {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let k = |k: &str| {
+      k.parse()
+        .map_err(|_| format!("`{}` is not a number of scores", k))
+    };
+    match s {
+      "mean" => Ok(Aggregator::Mean),
+      "median" => Ok(Aggregator::Median),
+      _ => {
+        if let Some(n) = s.strip_prefix("trimmed-") {
+          Ok(Aggregator::Trimmed(k(n)?))
+        } else if let Some(n) = s.strip_prefix("winsorized-") {
+          Ok(Aggregator::Winsorized(k(n)?))
+        } else {
+          Err(format!(
+            "unknown aggregator `{}`, expected mean, median, trimmed-<k> or winsorized-<k>",
+            s
+          ))
+        }
+      }
+    }
+  }
+}
+
+impl From<Aggregator> for String {
+  fn from(aggregator: Aggregator) -> Self {
+    aggregator.to_string()
+  }
+}
+
+impl TryFrom<String> for Aggregator {
+  type Error = String;
+
+  fn try_from(s: String) -> Result<Self, Self::Error> {
+    s.parse()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const VALUES: [f64; 5] = [100., 3., 0., 5., 2.];
+
+  #[test]
+  fn competition_ranks() {
+    assert_eq!(ranks(&[2., 5., 2., 1.]), [2, 1, 2, 4]);
+    assert_eq!(ranks(&[f64::NAN, 1., -f64::NAN, 3.]), [3, 2, 3, 1]);
+    assert_eq!(ranks(&[f64::NAN, f64::NEG_INFINITY, 0.]), [3, 2, 1]);
+
+    let mut values = [1., f64::NAN, 3., 2.];
+    values.sort_by(|&a, &b| order(a, b, true));
+    assert_eq!(format!("{:?}", values), "[1.0, 2.0, 3.0, NaN]");
+    values.sort_by(|&a, &b| order(a, b, false));
+    assert_eq!(format!("{:?}", values), "[3.0, 2.0, 1.0, NaN]");
+  }
+
+  #[test]
+  fn trimmed() {
+    assert_eq!(Aggregator::Trimmed(0).apply(&VALUES), 22.);
+    assert_eq!(Aggregator::Trimmed(1).apply(&VALUES), 10. / 3.);
+    assert_eq!(Aggregator::Trimmed(2).apply(&VALUES), 3.);
+  }
+
+  #[test]
+  fn winsorized() {
+    assert_eq!(Aggregator::Winsorized(0).apply(&VALUES), 22.);
+    assert_eq!(Aggregator::Winsorized(1).apply(&VALUES), 3.4);
+    assert_eq!(Aggregator::Winsorized(2).apply(&VALUES), 3.);
+  }
+
+  #[test]
+  fn too_few_values_fall_back_to_median() {
+    for aggregator in [Aggregator::Trimmed(3), Aggregator::Winsorized(3)] {
+      assert_eq!(aggregator.apply(&VALUES), 3.);
+      assert_eq!(aggregator.apply(&[1., 4.]), 2.5);
+      assert_eq!(aggregator.apply(&[7.]), 7.);
+      assert!(aggregator.apply(&[]).is_nan());
+    }
+    assert_eq!(Aggregator::Trimmed(1).apply(&[1., 4.]), 2.5);
+  }
+
+  #[test]
+  fn names_round_trip() {
+    for aggregator in Aggregator::COMMON {
+      assert_eq!(aggregator.to_string().parse(), Ok(aggregator));
+    }
+    assert!("trimmed-x".parse::<Aggregator>().is_err());
+    assert!("mode".parse::<Aggregator>().is_err());
+  }
+}
@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+
+use crate::{
+  model::Score,
+  normalize::{Normalizer, Rank},
+};
+
+/// A correlation coefficient between the scores of two judges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Correlation {
+  Pearson,
+  Spearman,
+  /// Kendall's τ-b, which accounts for ties.
+  Kendall,
+}
+
+impl Correlation {
+  pub const ALL: [Correlation; 3] = [
+    Correlation::Pearson,
+    Correlation::Spearman,
+    Correlation::Kendall,
+  ];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Correlation::Pearson => "pearson",
+      Correlation::Spearman => "spearman",
+      Correlation::Kendall => "kendall",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|c| c.name() == name)
+  }
+
+  /// The coefficient between paired samples, or `None` when it is undefined,
+  /// as for fewer than two pairs or a constant sample.
+  pub fn between(&self, a: &[f64], b: &[f64]) -> Option<f64> {
+    match self {
+      Correlation::Pearson => pearson(a, b),
+      Correlation::Spearman => spearman(a, b),
+      Correlation::Kendall => kendall(a, b),
+    }
+  }
+}
+
+pub fn pearson(a: &[f64], b: &[f64]) -> Option<f64> {
+  let n = a.len().min(b.len());
+  if n < 2 {
+    return None;
+  }
+  let (a, b) = (&a[..n], &b[..n]);
+  let mean_a = a.iter().sum::<f64>() / n as f64;
+  let mean_b = b.iter().sum::<f64>() / n as f64;
+
+  let mut cov = 0.;
+  let mut var_a = 0.;
+  let mut var_b = 0.;
+  for (x, y) in a.iter().zip(b) {
+    cov += (x - mean_a) * (y - mean_b);
+    var_a += (x - mean_a).powi(2);
+    var_b += (y - mean_b).powi(2);
+  }
+
+  if var_a == 0. || var_b == 0. {
+    return None;
+  }
+  Some(cov / (var_a * var_b).sqrt())
+}
+
+/// Pearson's coefficient between the ranks of the samples, ties sharing their
+/// average rank.
+pub fn spearman(a: &[f64], b: &[f64]) -> Option<f64> {
+  let a = Rank.scale_dimension(a.to_vec()).ok()?;
+  let b = Rank.scale_dimension(b.to_vec()).ok()?;
+  pearson(&a, &b)
+}
+
+pub fn kendall(a: &[f64], b: &[f64]) -> Option<f64> {
+  let n = a.len().min(b.len());
+  if n < 2 {
+    return None;
+  }
+
+  let mut score = 0.;
+  let mut tied_a = 0.;
+  let mut tied_b = 0.;
+  for i in 0..n {
+    for j in i + 1..n {
+      let da = sign(a[i], a[j]);
+      let db = sign(b[i], b[j]);
+      score += da * db;
+      if da == 0. {
+        tied_a += 1.;
+      }
+      if db == 0. {
+        tied_b += 1.;
+      }
+    }
+  }
+
+  let pairs = (n * (n - 1) / 2) as f64;
+  let denominator = ((pairs - tied_a) * (pairs - tied_b)).sqrt();
+  if denominator == 0. {
+    return None;
+  }
+  Some(score / denominator)
+}
+
+fn sign(x: f64, y: f64) -> f64 {
+  x.partial_cmp(&y).map_or(0., |o| o as i8 as f64)
+}
+
+/// Every judge of `scores`, sorted.
+pub fn judges(scores: &[Score]) -> Vec<String> {
+  let mut owners: Vec<_> = scores.iter().map(|s| s.owner.clone()).collect();
+  owners.sort();
+  owners.dedup();
+  owners
+}
+
+/// The coefficient between every two of `judges`, over the posts both of them
+/// scored. `value` picks what is compared, e.g. one dimension, and is `None`
+/// where the judge gave no such value.
+pub fn correlation_matrix(
+  scores: &[Score],
+  judges: &[String],
+  correlation: Correlation,
+  value: impl Fn(&Score) -> Option<f64>,
+) -> Vec<Vec<Option<f64>>> {
+  let by_judge: Vec<BTreeMap<usize, f64>> = judges
+    .iter()
+    .map(|judge| {
+      scores
+        .iter()
+        .filter(|s| &s.owner == judge)
+        .filter_map(|s| Some((s.post_id, value(s)?)))
+        .collect()
+    })
+    .collect();
+
+  by_judge
+    .iter()
+    .map(|a| {
+      by_judge
+        .iter()
+        .map(|b| {
+          let (x, y): (Vec<_>, Vec<_>) = a
+            .iter()
+            .filter_map(|(post, x)| Some((*x, *b.get(post)?)))
+            .unzip();
+          correlation.between(&x, &y)
+        })
+        .collect()
+    })
+    .collect()
+}
+
+/// The values given to each post, by every judge that gave one.
+pub fn units(scores: &[Score], value: impl Fn(&Score) -> Option<f64>) -> Vec<Vec<f64>> {
+  let mut units = BTreeMap::<_, Vec<_>>::new();
+  for score in scores {
+    if let Some(value) = value(score) {
+      units.entry(score.post_id).or_default().push(value);
+    }
+  }
+  units.into_values().collect()
+}
+
+/// Krippendorff's α with the interval metric. Units with a single value are
+/// not pairable and thus ignored.
+pub fn krippendorff_alpha(units: &[Vec<f64>]) -> Option<f64> {
+  // Sum of the squared differences over every ordered pair of values.
+  fn pairs(sum: f64, sum_sq: f64, n: f64) -> f64 {
+    2. * (n * sum_sq - sum * sum)
+  }
+
+  let mut observed = 0.;
+  let (mut sum, mut sum_sq, mut n) = (0., 0., 0.);
+  for unit in units.iter().filter(|u| u.len() >= 2) {
+    let m = unit.len() as f64;
+    let s = unit.iter().sum::<f64>();
+    let s2 = unit.iter().map(|v| v * v).sum::<f64>();
+    observed += pairs(s, s2, m) / (m - 1.);
+    sum += s;
+    sum_sq += s2;
+    n += m;
+  }
+
+  if n < 2. {
+    return None;
+  }
+  let observed = observed / n;
+  let expected = pairs(sum, sum_sq, n) / (n * (n - 1.));
+  if expected == 0. {
+    return None;
+  }
+  Some(1. - observed / expected)
+}
+
+/// One-way random-effects intraclass correlation ICC(1), allowing units to
+/// have been scored by different numbers of judges.
+pub fn icc(units: &[Vec<f64>]) -> Option<f64> {
+  let units: Vec<_> = units.iter().filter(|u| !u.is_empty()).collect();
+  let k = units.len() as f64;
+  let n = units.iter().map(|u| u.len()).sum::<usize>() as f64;
+  if k < 2. || n <= k {
+    return None;
+  }
+
+  let grand = units.iter().flat_map(|u| u.iter()).sum::<f64>() / n;
+  let mut between = 0.;
+  let mut within = 0.;
+  for unit in &units {
+    let m = unit.len() as f64;
+    let mean = unit.iter().sum::<f64>() / m;
+    between += m * (mean - grand).powi(2);
+    within += unit.iter().map(|v| (v - mean).powi(2)).sum::<f64>();
+  }
+
+  let ms_between = between / (k - 1.);
+  let ms_within = within / (n - k);
+  let k0 = (n - units.iter().map(|u| (u.len() as f64).powi(2)).sum::<f64>() / n) / (k - 1.);
+
+  let denominator = ms_between + (k0 - 1.) * ms_within;
+  if denominator == 0. {
+    return None;
+  }
+  Some((ms_between - ms_within) / denominator)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_close(value: Option<f64>, expected: f64, eps: f64) {
+    let value = value.unwrap();
+    assert!((value - expected).abs() < eps, "{} != {}", value, expected);
+  }
+
+  #[test]
+  fn kendall_tau_b() {
+    // The example of SciPy's `kendalltau`.
+    let x = [12., 2., 1., 12., 2.];
+    let y = [1., 4., 7., 1., 0.];
+    assert_close(kendall(&x, &y), -0.47140452079103173, 1e-12);
+    assert_close(kendall(&x, &x), 1., 1e-12);
+    assert_eq!(kendall(&x, &[3.; 5]), None);
+    assert_eq!(kendall(&[1.], &[2.]), None);
+  }
+
+  #[test]
+  fn krippendorff_interval() {
+    // Krippendorff (2011), "Computing Krippendorff's Alpha-Reliability":
+    // four coders, twelve units, interval α = 0.849.
+    let units = [
+      vec![1., 1., 1.],
+      vec![2., 2., 3., 2.],
+      vec![3., 3., 3., 3.],
+      vec![3., 3., 3., 3.],
+      vec![2., 2., 2., 2.],
+      vec![1., 2., 3., 4.],
+      vec![4., 4., 4., 4.],
+      vec![1., 1., 2., 1.],
+      vec![2., 2., 2., 2.],
+      vec![5., 5., 5.],
+      vec![1., 1.],
+      vec![3.],
+    ];
+    assert_close(krippendorff_alpha(&units), 0.849, 5e-4);
+    assert_eq!(krippendorff_alpha(&[vec![1.], vec![2.]]), None);
+    assert_eq!(krippendorff_alpha(&[vec![2., 2.], vec![2., 2.]]), None);
+  }
+
+  #[test]
+  fn icc_one_way() {
+    // Shrout & Fleiss (1979): six targets rated by four judges, ICC(1,1)
+    // = 0.166.
+    let units = [
+      vec![9., 2., 5., 8.],
+      vec![6., 1., 3., 2.],
+      vec![8., 4., 6., 8.],
+      vec![7., 1., 2., 6.],
+      vec![10., 5., 6., 9.],
+      vec![6., 2., 4., 7.],
+    ];
+    assert_close(icc(&units), 0.166, 5e-4);
+    assert_eq!(icc(&[vec![1.], vec![2.]]), None);
+  }
+}
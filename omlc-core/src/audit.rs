@@ -0,0 +1,511 @@
+use std::{collections::HashMap, io::Read};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  agreement::spearman,
+  columns::position,
+  config::Config,
+  dimension::Dimension,
+  load::line_of,
+  model::{median, Post, Score},
+  normalize::{MinMax, Normalizer},
+  resolve::TitleResolver,
+};
+
+/// How far a judge's derived columns may stray from ours before a row is
+/// flagged, as a fraction of the column's range.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AuditSettings {
+  pub tolerance: f64,
+  /// How far the factor of a dimension in a judge's totals, divided by the
+  /// dimension's weight, may stray from the median of those, as a fraction
+  /// of it.
+  pub weight_tolerance: f64,
+}
+
+impl Default for AuditSettings {
+  fn default() -> Self {
+    AuditSettings {
+      tolerance: 0.01,
+      weight_tolerance: 0.3,
+    }
+  }
+}
+
+/// A column judges' spreadsheets compute from their raw scores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DerivedColumn {
+  /// The score of a dimension rescaled by the judge, e.g. `文采`.
+  Dimension(usize),
+  /// The weighted total, e.g. `总分`.
+  Total,
+}
+
+impl DerivedColumn {
+  pub fn name(&self, dimensions: &[Dimension]) -> String {
+    match *self {
+      DerivedColumn::Dimension(i) => dimensions[i].name.clone(),
+      DerivedColumn::Total => "Total".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Discrepancy {
+  /// The cell does not hold a number, e.g. a spreadsheet error.
+  NotANumber { cell: String },
+  /// Normalized like the raw column, the derived value does not land where
+  /// the raw one does.
+  Dimension {
+    given: f64,
+    normalized: f64,
+    expected: f64,
+  },
+  /// The total is not the weighted sum the judge's other rows use.
+  Total { given: f64, expected: f64 },
+}
+
+/// A derived cell that disagrees with our computation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditFinding {
+  pub post_id: usize,
+  /// Line of the row in the judge's CSV.
+  pub line: usize,
+  pub column: DerivedColumn,
+  pub discrepancy: Discrepancy,
+}
+
+impl AuditFinding {
+  pub fn describe(&self) -> String {
+    match &self.discrepancy {
+      Discrepancy::NotANumber { cell } => format!("`{}` is not a number", cell),
+      Discrepancy::Dimension {
+        given,
+        normalized,
+        expected,
+      } => format!(
+        "{} normalizes to {:.3}, the raw score to {:.3}",
+        given, normalized, expected
+      ),
+      Discrepancy::Total { given, expected } => format!(
+        "{} where the other rows' formula gives {:.3}",
+        given, expected
+      ),
+    }
+  }
+}
+
+/// A judge's derived columns checked against our pipeline.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JudgeAudit {
+  pub owner: String,
+  /// The derived columns found in the CSV.
+  pub columns: Vec<DerivedColumn>,
+  /// Factor of each dimension in the judge's totals, fitted over all rows;
+  /// it applies to the derived column of the dimension if there is one and
+  /// to the raw one otherwise.
+  pub total_factors: Option<Vec<f64>>,
+  /// Spearman correlation between the judge's totals and our Σ of their
+  /// scores. Their spreadsheet normalizes differently, so the two only
+  /// agree on the order.
+  pub total_correlation: Option<f64>,
+  /// The weights [`Self::total_factors`] imply, in the units of
+  /// [`Dimension::weight`], if they stray from those by more than
+  /// [`AuditSettings::weight_tolerance`].
+  pub mismatched_weights: Option<Vec<f64>>,
+  pub findings: Vec<AuditFinding>,
+}
+
+impl JudgeAudit {
+  /// The judge's total formula as fitted, e.g. `1.515 × Literary + …`.
+  pub fn total_formula(&self, dimensions: &[Dimension]) -> Option<String> {
+    let terms: Vec<_> = self
+      .total_factors
+      .as_ref()?
+      .iter()
+      .zip(dimensions)
+      .map(|(f, d)| format!("{:.3} × {}", f, d.name))
+      .collect();
+    Some(terms.join(" + "))
+  }
+
+  /// Describes [`Self::mismatched_weights`], e.g. `weights 1.01 : 1.00 :
+  /// 0.98 instead of 1 : 1 : 1.5`.
+  pub fn describe_weights(&self, dimensions: &[Dimension]) -> Option<String> {
+    let implied: Vec<_> = self
+      .mismatched_weights
+      .as_ref()?
+      .iter()
+      .map(|w| format!("{:.2}", w))
+      .collect();
+    let weights: Vec<_> = dimensions.iter().map(|d| d.weight.to_string()).collect();
+    Some(format!(
+      "weights {} instead of {}",
+      implied.join(" : "),
+      weights.join(" : ")
+    ))
+  }
+}
+
+/// A derived cell of a row whose post the judge scored.
+struct Cell<'a> {
+  score: &'a Score,
+  line: usize,
+  value: f64,
+}
+
+/// Normalizes `values` with the method of `config`, after stretching them
+/// onto the same range so that rescaled columns compare equal.
+fn normalize_column(values: Vec<f64>, config: &Config) -> Result<Vec<f64>> {
+  let values = MinMax::DEFAULT.scale_dimension(values)?;
+  config.normalizer.scale_dimension(values)
+}
+
+fn range(values: &[f64]) -> f64 {
+  let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+  let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+  if max > min {
+    max - min
+  } else {
+    1.
+  }
+}
+
+/// Least-squares factors `c` such that `rows[i] · c ≈ targets[i]`; `None`
+/// when they are not determined.
+fn fit(rows: &[Vec<f64>], targets: &[f64]) -> Option<Vec<f64>> {
+  let k = rows.first()?.len();
+  if rows.len() <= k {
+    return None;
+  }
+
+  // Normal equations, solved by Gaussian elimination.
+  let mut a: Vec<Vec<f64>> = (0..k)
+    .map(|i| {
+      let mut row: Vec<_> = (0..k)
+        .map(|j| rows.iter().map(|r| r[i] * r[j]).sum())
+        .collect();
+      row.push(rows.iter().zip(targets).map(|(r, t)| r[i] * t).sum());
+      row
+    })
+    .collect();
+  for i in 0..k {
+    let pivot = (i..k).max_by(|&x, &y| a[x][i].abs().total_cmp(&a[y][i].abs()))?;
+    if a[pivot][i].abs() < 1e-12 {
+      return None;
+    }
+    a.swap(i, pivot);
+    for j in 0..k {
+      if j != i {
+        let f = a[j][i] / a[i][i];
+        let pivot_row = a[i].clone();
+        for (x, p) in a[j].iter_mut().zip(pivot_row) {
+          *x -= f * p;
+        }
+      }
+    }
+  }
+  Some((0..k).map(|i| a[i][k] / a[i][i]).collect())
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+  a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Like [`fit`], but leaves out the rows that disagree most one by one until
+/// the others agree within `tolerance` of the range of `targets`, so that a
+/// few wrong rows do not skew the factors. At least half the rows are kept.
+fn robust_fit(rows: &[Vec<f64>], targets: &[f64], tolerance: f64) -> Option<Vec<f64>> {
+  let range = range(targets);
+  let mut kept: Vec<_> = (0..rows.len()).collect();
+  loop {
+    let kept_rows: Vec<_> = kept.iter().map(|&i| rows[i].clone()).collect();
+    let kept_targets: Vec<_> = kept.iter().map(|&i| targets[i]).collect();
+    let factors = fit(&kept_rows, &kept_targets)?;
+
+    let (worst, error) = kept
+      .iter()
+      .map(|&i| (targets[i] - dot(&rows[i], &factors)).abs() / range)
+      .enumerate()
+      .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if error <= tolerance || 2 * kept.len() <= rows.len() {
+      return Some(factors);
+    }
+    kept.remove(worst);
+  }
+}
+
+/// The weights `factors` imply, in the units of the dimensions' weights, if
+/// they stray from those by more than `settings` allow.
+fn mismatched_weights(
+  factors: &[f64],
+  dimensions: &[Dimension],
+  settings: &AuditSettings,
+) -> Option<Vec<f64>> {
+  let ratios: Vec<_> = factors
+    .iter()
+    .zip(dimensions)
+    .filter(|(_, d)| d.weight > 0.)
+    .map(|(f, d)| f / d.weight)
+    .collect();
+  // With three or more dimensions, one wrong weight leaves the median be.
+  let unit = median(&ratios);
+  if ratios
+    .iter()
+    .all(|r| (r / unit - 1.).abs() <= settings.weight_tolerance)
+  {
+    return None;
+  }
+  Some(factors.iter().map(|f| f / unit).collect())
+}
+
+/// Checks the derived columns of one judge's CSV against `scores`, the
+/// judge's scores as loaded and normalized from the same file.
+///
+/// Every derived dimension column is normalized like the raw one, so a judge
+/// merely rescaling their scores agrees; the total must be the same weighted
+/// sum of the dimensions on every row, with factors roughly proportional to
+/// the dimensions' weights. Rows the loader rejected are skipped.
+pub fn audit_judge_csv<R: Read>(
+  owner: &str,
+  mut reader: R,
+  posts: &[Post],
+  scores: &[Score],
+  config: &Config,
+) -> Result<JudgeAudit> {
+  let dimensions = &config.dimensions;
+  let tolerance = config.audit.tolerance;
+
+  let mut bytes = Vec::new();
+  reader.read_to_end(&mut bytes)?;
+  let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&bytes);
+  let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(bytes);
+  let header = reader.headers()?.clone();
+
+  let title = position(&header, &config.columns.title);
+  let columns: Vec<_> = dimensions
+    .iter()
+    .enumerate()
+    .filter_map(|(i, d)| {
+      Some((
+        DerivedColumn::Dimension(i),
+        position(&header, &d.derived_columns)?,
+      ))
+    })
+    .chain(position(&header, &config.columns.total).map(|c| (DerivedColumn::Total, c)))
+    .collect();
+
+  let mut audit = JudgeAudit {
+    owner: owner.to_string(),
+    columns: columns.iter().map(|&(column, _)| column).collect(),
+    ..JudgeAudit::default()
+  };
+  let Some(title) = title else {
+    return Ok(audit);
+  };
+
+  let resolver = TitleResolver::new(posts);
+  let by_post: HashMap<_, _> = scores.iter().map(|s| (s.post_id, s)).collect();
+
+  // The numeric cells of every derived column, by column.
+  let mut cells: HashMap<DerivedColumn, Vec<Cell>> = HashMap::new();
+  for record in reader.records() {
+    let record = record?;
+    let line = record.position().map_or(0, |p| line_of(bytes, p) as usize);
+    let Some(score) = record
+      .get(title)
+      .and_then(|key| resolver.resolve(key.trim()).ok())
+      .and_then(|post_id| by_post.get(&post_id))
+    else {
+      continue;
+    };
+
+    for &(column, index) in &columns {
+      let cell = record.get(index).unwrap_or_default().trim();
+      if cell.is_empty() {
+        continue;
+      }
+      match cell.parse() {
+        Ok(value) => cells
+          .entry(column)
+          .or_default()
+          .push(Cell { score, line, value }),
+        Err(_) => audit.findings.push(AuditFinding {
+          post_id: score.post_id,
+          line,
+          column,
+          discrepancy: Discrepancy::NotANumber {
+            cell: cell.to_string(),
+          },
+        }),
+      }
+    }
+  }
+
+  for (i, _) in dimensions.iter().enumerate() {
+    let column = DerivedColumn::Dimension(i);
+    // Only the rows with a raw value to compare with.
+    let column_cells: Vec<_> = cells
+      .get(&column)
+      .into_iter()
+      .flatten()
+      .filter_map(|c| Some((c, c.score.raw[i]?)))
+      .collect();
+    if column_cells.len() < 2 {
+      continue;
+    }
+    let expected = normalize_column(column_cells.iter().map(|&(_, raw)| raw).collect(), config)?;
+    let normalized = normalize_column(column_cells.iter().map(|(c, _)| c.value).collect(), config)?;
+    let range = range(&expected);
+
+    for (((cell, _), normalized), expected) in column_cells.iter().zip(normalized).zip(expected) {
+      if (normalized - expected).abs() / range > tolerance {
+        audit.findings.push(AuditFinding {
+          post_id: cell.score.post_id,
+          line: cell.line,
+          column,
+          discrepancy: Discrepancy::Dimension {
+            given: cell.value,
+            normalized,
+            expected,
+          },
+        });
+      }
+    }
+  }
+
+  if let Some(totals) = cells.get(&DerivedColumn::Total) {
+    // What the judge's spreadsheet shows for each dimension of a post.
+    let shown = |post_id: usize, i: usize| {
+      let column = DerivedColumn::Dimension(i);
+      match cells.get(&column) {
+        Some(column_cells) => column_cells
+          .iter()
+          .find(|c| c.score.post_id == post_id)
+          .map(|c| c.value),
+        None if audit.columns.contains(&column) => None,
+        None => by_post.get(&post_id).and_then(|s| s.raw[i]),
+      }
+    };
+
+    let (rows, totals): (Vec<_>, Vec<_>) = totals
+      .iter()
+      .filter_map(|cell| {
+        let row: Option<Vec<_>> = (0..dimensions.len())
+          .map(|i| shown(cell.score.post_id, i))
+          .collect();
+        Some((row?, cell))
+      })
+      .unzip();
+
+    let given: Vec<_> = totals.iter().map(|c| c.value).collect();
+    let (compared, ours): (Vec<_>, Vec<_>) = totals
+      .iter()
+      .filter_map(|c| Some((c.value, c.score.sum(dimensions)?)))
+      .unzip();
+    audit.total_correlation = spearman(&compared, &ours);
+
+    if let Some(factors) = robust_fit(&rows, &given, tolerance) {
+      let range = range(&given);
+      for (row, cell) in rows.iter().zip(&totals) {
+        let expected = dot(row, &factors);
+        if (cell.value - expected).abs() / range > tolerance {
+          audit.findings.push(AuditFinding {
+            post_id: cell.score.post_id,
+            line: cell.line,
+            column: DerivedColumn::Total,
+            discrepancy: Discrepancy::Total {
+              given: cell.value,
+              expected,
+            },
+          });
+        }
+      }
+      audit.mismatched_weights = mismatched_weights(&factors, dimensions, &config.audit);
+      audit.total_factors = Some(factors);
+    }
+  }
+
+  audit.findings.sort_by_key(|f| f.line);
+  Ok(audit)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{load::load_judge_csv, normalize::normalize};
+
+  #[test]
+  fn crlf_finding_lines() {
+    let posts: Vec<_> = ["Alpha", "Beta", "Gamma"]
+      .iter()
+      .map(|title| Post {
+        id: title.to_lowercase(),
+        title: title.to_string(),
+        author: "someone".to_string(),
+        url: format!("https://example.com/{}", title.to_lowercase()),
+      })
+      .collect();
+    let csv = "title,原始评分-文采,原始评分-思维,原始评分-魔怔,文采\r\n\
+               Alpha,7,6,9,7\r\n\
+               Beta,5,7,8,#VALUE!\r\n\
+               Gamma,3,4,5,3\r\n";
+    let config = Config::default();
+
+    let mut scores = load_judge_csv("judge", csv.as_bytes(), &posts, &config).unwrap();
+    normalize(&mut scores, &config).unwrap();
+    let audit = audit_judge_csv("judge", csv.as_bytes(), &posts, &scores, &config).unwrap();
+
+    assert_eq!(audit.findings.len(), 1);
+    assert_eq!(audit.findings[0].line, 3);
+    assert_eq!(audit.findings[0].post_id, 1);
+  }
+
+  #[test]
+  fn dropped_weight() {
+    let posts: Vec<_> = ["Alpha", "Beta", "Gamma", "Delta", "Epsilon"]
+      .iter()
+      .map(|title| Post {
+        id: title.to_lowercase(),
+        title: title.to_string(),
+        author: "someone".to_string(),
+        url: format!("https://example.com/{}", title.to_lowercase()),
+      })
+      .collect();
+    let raw = [
+      [7., 6., 9.],
+      [5., 7., 8.],
+      [3., 4., 5.],
+      [8., 9., 3.],
+      [6., 2., 7.],
+    ];
+    let config = Config::default();
+    let audit = |weights: [f64; 3]| {
+      let mut csv = "title,原始评分-文采,原始评分-思维,原始评分-魔怔,总分\n".to_string();
+      for (post, values) in posts.iter().zip(raw) {
+        let total: f64 = values.iter().zip(weights).map(|(v, w)| 4. * v * w).sum();
+        csv += &format!(
+          "{},{},{},{},{}\n",
+          post.title, values[0], values[1], values[2], total
+        );
+      }
+      let mut scores = load_judge_csv("judge", csv.as_bytes(), &posts, &config).unwrap();
+      normalize(&mut scores, &config).unwrap();
+      audit_judge_csv("judge", csv.as_bytes(), &posts, &scores, &config).unwrap()
+    };
+
+    let audit_ok = audit([1., 1., 1.5]);
+    assert!(audit_ok.findings.is_empty());
+    assert_eq!(audit_ok.mismatched_weights, None);
+
+    let dropped = audit([1., 1., 1.]);
+    assert!(dropped.findings.is_empty());
+    assert_eq!(
+      dropped.describe_weights(&config.dimensions).unwrap(),
+      "weights 1.00 : 1.00 : 1.00 instead of 1 : 1 : 1.5"
+    );
+  }
+}
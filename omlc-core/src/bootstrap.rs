@@ -0,0 +1,225 @@
+use crate::{
+  aggregate::{ranks, Aggregator},
+  dimension::Dimension,
+  model::PostWithScores,
+};
+
+/// SplitMix64, a tiny generator that is good enough for resampling and keeps
+/// the results identical from one load to the next.
+struct Rng(u64);
+
+impl Rng {
+  fn next(&mut self) -> u64 {
+    self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = self.0;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+  }
+
+  /// A number in `0..n`.
+  fn below(&mut self, n: usize) -> usize {
+    (self.next() % n as u64) as usize
+  }
+}
+
+/// How certain a post's Σ and rank are, from resampling the judges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interval {
+  /// 95% interval of the aggregated Σ.
+  pub low: f64,
+  pub high: f64,
+  /// 95% interval of the rank.
+  pub best_rank: usize,
+  pub worst_rank: usize,
+  /// Probability of each rank, starting with the first.
+  pub rank_probabilities: Vec<f64>,
+}
+
+impl Interval {
+  /// The most likely ranks with their probabilities, most likely first.
+  pub fn likely_ranks(&self, n: usize) -> Vec<(usize, f64)> {
+    let mut ranks: Vec<_> = (1..)
+      .zip(self.rank_probabilities.iter().copied())
+      .filter(|&(_, p)| p > 0.)
+      .collect();
+    ranks.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranks.truncate(n);
+    ranks
+  }
+}
+
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+  sorted[((sorted.len() - 1) as f64 * q).round() as usize]
+}
+
+/// Resamples the judges with replacement `resamples` times, aggregating each
+/// post's Σ over the drawn judges, and summarizes how Σ and ranks vary.
+pub fn bootstrap(
+  posts: &[PostWithScores],
+  dimensions: &[Dimension],
+  aggregator: Aggregator,
+  resamples: usize,
+  seed: u64,
+) -> Vec<Interval> {
+  let mut judges: Vec<_> = posts
+    .iter()
+    .flat_map(|p| p.scores.iter().map(|s| s.owner.as_str()))
+    .collect();
+  judges.sort();
+  judges.dedup();
+
+  // Each post's complete sums by judge index.
+  let sums: Vec<Vec<(usize, f64)>> = posts
+    .iter()
+    .map(|p| {
+      p.scores
+        .iter()
+        .filter_map(|s| {
+          let judge = judges.binary_search(&s.owner.as_str()).unwrap();
+          Some((judge, s.sum(dimensions)?))
+        })
+        .collect()
+    })
+    .collect();
+
+  let mut rng = Rng(seed);
+  let mut values = vec![Vec::with_capacity(resamples); posts.len()];
+  let mut rank_counts = vec![vec![0usize; posts.len()]; posts.len()];
+  let mut post_ranks = vec![Vec::with_capacity(resamples); posts.len()];
+
+  for _ in 0..resamples.max(1) {
+    let mut drawn = vec![0; judges.len()];
+    for _ in 0..judges.len() {
+      drawn[rng.below(judges.len())] += 1;
+    }
+
+    let resampled: Vec<_> = sums
+      .iter()
+      .map(|post| {
+        let values: Vec<_> = post
+          .iter()
+          .flat_map(|&(judge, sum)| std::iter::repeat_n(sum, drawn[judge]))
+          .collect();
+        aggregator.apply(&values)
+      })
+      .collect();
+
+    // Posts no drawn judge scored rank last.
+    for (i, rank) in ranks(&resampled).into_iter().enumerate() {
+      rank_counts[i][rank - 1] += 1;
+      post_ranks[i].push(rank as f64);
+    }
+    for (i, v) in resampled.into_iter().enumerate() {
+      if !v.is_nan() {
+        values[i].push(v);
+      }
+    }
+  }
+
+  let total = resamples.max(1) as f64;
+  values
+    .into_iter()
+    .zip(post_ranks)
+    .zip(rank_counts)
+    .map(|((mut values, mut ranks), counts)| {
+      values.sort_by(f64::total_cmp);
+      ranks.sort_by(f64::total_cmp);
+      let (low, high) = if values.is_empty() {
+        (f64::NAN, f64::NAN)
+      } else {
+        (quantile(&values, 0.025), quantile(&values, 0.975))
+      };
+      Interval {
+        low,
+        high,
+        best_rank: quantile(&ranks, 0.025) as usize,
+        worst_rank: quantile(&ranks, 0.975) as usize,
+        rank_probabilities: counts.into_iter().map(|c| c as f64 / total).collect(),
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    aggregate::aggregate,
+    config::Config,
+    model::{Post, Score},
+  };
+
+  fn posts_with_scores() -> (Vec<PostWithScores>, Config) {
+    let config = Config::default();
+    let posts: Vec<_> = (0..4)
+      .map(|i| Post {
+        id: i.to_string(),
+        title: format!("Post {}", i),
+        author: "someone".to_string(),
+        url: format!("https://example.com/{}", i),
+      })
+      .collect();
+    // Nobody scored the last post.
+    let sums = [
+      ("a", [0.9, 0.1, -0.5]),
+      ("b", [0.4, 0.6, -0.2]),
+      ("c", [0.7, 0.3, 0.2]),
+      ("d", [0.2, 0.5, -0.8]),
+    ];
+    let scores: Vec<_> = sums
+      .iter()
+      .flat_map(|(owner, values)| {
+        let config = &config;
+        values.iter().enumerate().map(move |(post_id, &v)| {
+          Score::new(
+            owner.to_string(),
+            post_id,
+            vec![Some(v); 3],
+            config.raw_scales(owner),
+            None,
+          )
+        })
+      })
+      .collect();
+    (aggregate(&posts, &scores).unwrap(), config)
+  }
+
+  #[test]
+  fn deterministic_for_a_seed() {
+    let (posts, config) = posts_with_scores();
+    // Compared as text, as the unscored post's NaN bounds never compare equal.
+    let run = |seed| {
+      let intervals = bootstrap(&posts, &config.dimensions, Aggregator::Mean, 200, seed);
+      format!("{:?}", intervals)
+    };
+    assert_eq!(run(7), run(7));
+    assert_ne!(run(7), run(8));
+  }
+
+  #[test]
+  fn intervals_are_consistent() {
+    let (posts, config) = posts_with_scores();
+    let intervals = bootstrap(&posts, &config.dimensions, Aggregator::Mean, 500, 1);
+
+    for interval in &intervals {
+      let total: f64 = interval.rank_probabilities.iter().sum();
+      assert!((total - 1.).abs() < 1e-9, "{}", total);
+      assert!(interval.best_rank <= interval.worst_rank);
+    }
+    for interval in &intervals[..3] {
+      assert!(interval.low <= interval.high);
+    }
+  }
+
+  #[test]
+  fn unscored_post_ranks_last() {
+    let (posts, config) = posts_with_scores();
+    let intervals = bootstrap(&posts, &config.dimensions, Aggregator::Mean, 100, 1);
+
+    let unscored = &intervals[3];
+    assert!(unscored.low.is_nan() && unscored.high.is_nan());
+    assert_eq!((unscored.best_rank, unscored.worst_rank), (4, 4));
+    assert_eq!(unscored.rank_probabilities, [0., 0., 0., 1.]);
+  }
+}
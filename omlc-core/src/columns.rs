@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{diagnostics::Problem, dimension::Dimension};
+
+/// Header names accepted for the post column a judge's CSV must provide, for
+/// the optional comment column and for the total some judges' spreadsheets
+/// compute. Score columns are named by each [`Dimension`].
+///
+/// The `title` column may hold a post's title, id or URL.
+///
+/// Matching ignores surrounding whitespace, a leading BOM and ASCII case.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ColumnAliases {
+  pub title: Vec<String>,
+  pub comment: Vec<String>,
+  pub total: Vec<String>,
+}
+
+impl Default for ColumnAliases {
+  fn default() -> Self {
+    fn names(names: &[&str]) -> Vec<String> {
+      names.iter().map(|s| s.to_string()).collect()
+    }
+
+    ColumnAliases {
+      title: names(&["title", "标题", "题目", "id", "url", "post"]),
+      comment: names(&["评论", "comment"]),
+      total: names(&["总分", "total"]),
+    }
+  }
+}
+
+/// Cell contents that stand for no score instead of a number.
+///
+/// Matching ignores surrounding whitespace and ASCII case.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CellMarkers {
+  /// A dimension the judge left unscored; blank cells always are.
+  pub missing: Vec<String>,
+  /// The judge declines to score the post at all, e.g. for a conflict of
+  /// interest.
+  pub abstained: Vec<String>,
+}
+
+impl Default for CellMarkers {
+  fn default() -> Self {
+    fn names(names: &[&str]) -> Vec<String> {
+      names.iter().map(|s| s.to_string()).collect()
+    }
+
+    CellMarkers {
+      missing: names(&["N/A", "NA", "-", "无"]),
+      abstained: names(&["回避", "弃权", "abstain", "abstained", "COI"]),
+    }
+  }
+}
+
+impl CellMarkers {
+  pub fn is_missing(&self, cell: &str) -> bool {
+    let cell = cell.trim();
+    cell.is_empty() || self.missing.iter().any(|m| m.eq_ignore_ascii_case(cell))
+  }
+
+  pub fn is_abstention(&self, cell: &str) -> bool {
+    let cell = cell.trim();
+    self.abstained.iter().any(|m| m.eq_ignore_ascii_case(cell))
+  }
+}
+
+/// Position of the first column of `header` named by one of `names`.
+pub(crate) fn position<'a>(
+  header: impl IntoIterator<Item = &'a str>,
+  names: &[String],
+) -> Option<usize> {
+  header.into_iter().position(|h| {
+    let h = h.trim_start_matches('\u{feff}').trim();
+    names.iter().any(|n| n.eq_ignore_ascii_case(h))
+  })
+}
+
+/// Positions of the required columns within a judge's CSV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMap {
+  pub title: usize,
+  /// One column per dimension, in the order of the dimensions.
+  pub values: Vec<usize>,
+  pub comment: Option<usize>,
+}
+
+impl ColumnMap {
+  /// Locates every required column in `header`, reporting those absent.
+  pub fn resolve<'a>(
+    header: impl IntoIterator<Item = &'a str> + Clone,
+    aliases: &ColumnAliases,
+    dimensions: &[Dimension],
+  ) -> Result<Self, Vec<Problem>> {
+    let position = |names: &[String]| position(header.clone(), names);
+
+    let mut missing = Vec::new();
+    let mut find = |names: &[String], fallback: &str| {
+      let pos = position(names);
+      if pos.is_none() {
+        missing.push(Problem::MissingColumn {
+          name: names.first().map_or(fallback, String::as_str).to_string(),
+        });
+      }
+      pos.unwrap_or_default()
+    };
+
+    let map = ColumnMap {
+      title: find(&aliases.title, "title"),
+      values: dimensions
+        .iter()
+        .map(|d| find(&d.columns, &d.key))
+        .collect(),
+      comment: position(&aliases.comment),
+    };
+
+    if missing.is_empty() {
+      Ok(map)
+    } else {
+      Err(missing)
+    }
+  }
+}
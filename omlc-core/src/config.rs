@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  audit::AuditSettings,
+  columns::{CellMarkers, ColumnAliases},
+  conflicts::ConflictSettings,
+  dimension::Dimension,
+  normalize::Method,
+  outliers::OutlierThresholds,
+};
+
+/// How judges' CSVs are read, normalized and summed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Config {
+  /// Criteria posts are scored on.
+  pub dimensions: Vec<Dimension>,
+  /// Header names recognized for the post and comment CSV columns.
+  pub columns: ColumnAliases,
+  /// Cells that mark a score as missing or the judge as abstaining.
+  pub markers: CellMarkers,
+  /// Normalization applied to every judge's scores.
+  pub normalizer: Method,
+  /// When scores and judges are flagged as outliers.
+  pub outliers: OutlierThresholds,
+  /// When judges' derived columns are flagged by the audit.
+  pub audit: AuditSettings,
+  /// How judges' scores of their own posts are treated.
+  pub conflicts: ConflictSettings,
+  /// Settings of individual judges, by handle.
+  pub judges: BTreeMap<String, JudgeSettings>,
+}
+
+/// What is known of one judge besides their CSV.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct JudgeSettings {
+  /// Highest raw score the judge gives on every dimension, when they score
+  /// on a scale of their own instead of the dimensions' `raw_scale`.
+  pub raw_scale: Option<f64>,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Config {
+      dimensions: Dimension::defaults(),
+      columns: ColumnAliases::default(),
+      markers: CellMarkers::default(),
+      normalizer: Method::default(),
+      outliers: OutlierThresholds::default(),
+      audit: AuditSettings::default(),
+      conflicts: ConflictSettings::default(),
+      judges: BTreeMap::new(),
+    }
+  }
+}
+
+impl Config {
+  /// The raw scale `owner` declared, if any.
+  pub fn declared_scale(&self, owner: &str) -> Option<f64> {
+    self.judges.get(owner)?.raw_scale
+  }
+
+  /// The raw scale `owner` scores each dimension on: the one they declared,
+  /// or else the dimension's.
+  pub fn raw_scales(&self, owner: &str) -> Vec<f64> {
+    let declared = self.declared_scale(owner);
+    self
+      .dimensions
+      .iter()
+      .map(|d| declared.unwrap_or(d.raw_scale))
+      .collect()
+  }
+}
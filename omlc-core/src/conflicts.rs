@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Post, Score};
+
+/// What becomes of a judge's score of their own post.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+  /// Kept for display but left out of every statistic.
+  #[default]
+  Exclude,
+  /// Counted, and marked wherever it is shown.
+  Flag,
+  /// Counted like any other score.
+  Include,
+}
+
+/// How judges' scores of their own posts are found and treated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ConflictSettings {
+  pub policy: ConflictPolicy,
+  /// The author names of judges who publish under another name than their
+  /// handle, by handle.
+  pub aliases: BTreeMap<String, Vec<String>>,
+}
+
+impl ConflictSettings {
+  /// Whether `owner` wrote `post`, under their handle or one of their
+  /// aliases. Matching ignores surrounding whitespace and ASCII case.
+  pub fn is_author(&self, owner: &str, post: &Post) -> bool {
+    let author = post.author.trim();
+    let matches = |name: &str| name.trim().eq_ignore_ascii_case(author);
+    matches(owner)
+      || self
+        .aliases
+        .get(owner)
+        .is_some_and(|names| names.iter().any(|n| matches(n)))
+  }
+
+  /// Marks `score` as a conflict of interest if its judge wrote `post`, and
+  /// drops its values under [`ConflictPolicy::Exclude`].
+  pub fn apply(&self, score: &mut Score, post: &Post) {
+    if self.policy == ConflictPolicy::Include || !self.is_author(&score.owner, post) {
+      return;
+    }
+    score.conflict = true;
+    if self.policy == ConflictPolicy::Exclude {
+      score.excluded = true;
+      score.values.fill(None);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{config::Config, normalize::normalize};
+
+  fn post(author: &str) -> Post {
+    Post {
+      id: "post".to_string(),
+      title: "Post".to_string(),
+      author: author.to_string(),
+      url: "https://example.com/post".to_string(),
+    }
+  }
+
+  fn score(owner: &str, post_id: usize, values: [f64; 3]) -> Score {
+    let config = Config::default();
+    Score::new(
+      owner.to_string(),
+      post_id,
+      values.into_iter().map(Some).collect(),
+      config.raw_scales(owner),
+      None,
+    )
+  }
+
+  fn settings(policy: ConflictPolicy) -> ConflictSettings {
+    ConflictSettings {
+      policy,
+      aliases: BTreeMap::from([("judge".to_string(), vec!["Pen Name".to_string()])]),
+    }
+  }
+
+  #[test]
+  fn exclude() {
+    let mut own = score("judge", 0, [7., 6., 9.]);
+    settings(ConflictPolicy::Exclude).apply(&mut own, &post("judge"));
+    assert!(own.conflict && own.excluded);
+    assert_eq!(own.values, [None; 3]);
+    assert_eq!(own.raw, [Some(7.), Some(6.), Some(9.)]);
+  }
+
+  #[test]
+  fn flag() {
+    let mut own = score("judge", 0, [7., 6., 9.]);
+    settings(ConflictPolicy::Flag).apply(&mut own, &post("judge"));
+    assert!(own.conflict && !own.excluded);
+    assert_eq!(own.values, own.raw);
+  }
+
+  #[test]
+  fn include() {
+    let original = score("judge", 0, [7., 6., 9.]);
+    let mut own = original.clone();
+    settings(ConflictPolicy::Include).apply(&mut own, &post("judge"));
+    assert_eq!(own, original);
+  }
+
+  #[test]
+  fn other_authors_are_left_alone() {
+    let original = score("judge", 0, [7., 6., 9.]);
+    let mut other = original.clone();
+    settings(ConflictPolicy::Exclude).apply(&mut other, &post("someone"));
+    assert_eq!(other, original);
+  }
+
+  #[test]
+  fn author_matching() {
+    let settings = settings(ConflictPolicy::Exclude);
+    assert!(settings.is_author("judge", &post("judge")));
+    assert!(settings.is_author("judge", &post("  JUDGE ")));
+    assert!(settings.is_author("judge", &post("pen name")));
+    assert!(settings.is_author("judge", &post(" Pen Name")));
+    assert!(!settings.is_author("judge", &post("Pen")));
+    assert!(!settings.is_author("other", &post("Pen Name")));
+  }
+
+  #[test]
+  fn excluded_scores_are_not_normalized() {
+    let config = Config::default();
+    let mut scores = vec![
+      score("judge", 0, [2., 2., 2.]),
+      score("judge", 1, [8., 8., 8.]),
+      score("judge", 2, [10., 10., 10.]),
+    ];
+    config.conflicts.apply(&mut scores[2], &post("judge"));
+    normalize(&mut scores, &config).unwrap();
+
+    assert_eq!(scores[2].values, [None; 3]);
+    // Only 2 and 8 are left, at either end of the scale; with 10 counted, 8
+    // would not be.
+    let (low, high) = (scores[0].values[0].unwrap(), scores[1].values[0].unwrap());
+    assert!(high > 0.);
+    assert_eq!(low, -high);
+  }
+}
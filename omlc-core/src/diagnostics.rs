@@ -0,0 +1,120 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// Something wrong with the judges' data.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Problem {
+  UnknownTitle {
+    title: String,
+    suggestions: Vec<String>,
+  },
+  DuplicateTitle {
+    title: String,
+    first_line: u64,
+  },
+  NonNumeric {
+    value: String,
+  },
+  MissingColumn {
+    name: String,
+  },
+  OutOfRange {
+    value: f64,
+    /// The judge's declared raw scale.
+    max: Option<f64>,
+  },
+  Encoding {
+    message: String,
+  },
+  Normalization {
+    message: String,
+  },
+}
+
+impl fmt::Display for Problem {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Problem::UnknownTitle { title, suggestions } => {
+        write!(f, "post `{}` not found", title)?;
+        for (i, s) in suggestions.iter().enumerate() {
+          write!(
+            f,
+            "{} `{}`",
+            if i == 0 { "; did you mean" } else { " or" },
+            s
+          )?;
+        }
+        Ok(())
+      }
+      Problem::DuplicateTitle { title, first_line } => {
+        write!(f, "title `{}` already scored on line {}", title, first_line)
+      }
+      Problem::NonNumeric { value } => write!(f, "`{}` is not a number", value),
+      Problem::MissingColumn { name } => write!(f, "missing column `{}`", name),
+      Problem::OutOfRange {
+        value,
+        max: Some(max),
+      } => write!(f, "score {} is not within 0–{}", value, max),
+      Problem::OutOfRange { value, .. } => write!(f, "score {} is out of range", value),
+      Problem::Encoding { message } => write!(f, "encoding error: {}", message),
+      Problem::Normalization { message } => write!(f, "normalization failed: {}", message),
+    }
+  }
+}
+
+/// A [`Problem`] together with where it was found.
+///
+/// `line` and `column` are 1-based; they are absent for problems concerning
+/// a whole file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+  pub file: String,
+  pub line: Option<u64>,
+  pub column: Option<usize>,
+  #[serde(flatten)]
+  pub problem: Problem,
+}
+
+impl Diagnostic {
+  pub fn file(file: &str, problem: Problem) -> Self {
+    Diagnostic {
+      file: file.to_string(),
+      line: None,
+      column: None,
+      problem,
+    }
+  }
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.file)?;
+    if let Some(line) = self.line {
+      write!(f, ":{}", line)?;
+      if let Some(column) = self.column {
+        write!(f, ":{}", column)?;
+      }
+    }
+    write!(f, ": {}", self.problem)
+  }
+}
+
+/// Every problem found in a file, returned when loading strictly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl fmt::Display for Diagnostics {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, d) in self.0.iter().enumerate() {
+      if i > 0 {
+        writeln!(f)?;
+      }
+      write!(f, "{}", d)?;
+    }
+    Ok(())
+  }
+}
+
+impl std::error::Error for Diagnostics {}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+/// One criterion judges score posts on.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Dimension {
+  /// Machine-readable name, used in URLs and CLI options.
+  pub key: String,
+  /// Full name, e.g. "Literary".
+  pub name: String,
+  /// Short name for table headers, e.g. "Lit.".
+  pub label: String,
+  /// Factor of this dimension in a score's weighted sum.
+  #[serde(default = "default_weight")]
+  pub weight: f64,
+  /// Maximum raw score judges are asked to give.
+  #[serde(default = "default_raw_scale")]
+  pub raw_scale: f64,
+  /// Header names of the raw score column in judges' CSVs.
+  #[serde(default)]
+  pub columns: Vec<String>,
+  /// Header names of the column judges' spreadsheets derive from the raw
+  /// one, checked by [`crate::audit_judge_csv`].
+  #[serde(default)]
+  pub derived_columns: Vec<String>,
+}
+
+fn default_weight() -> f64 {
+  1.
+}
+
+fn default_raw_scale() -> f64 {
+  10.
+}
+
+impl Dimension {
+  fn new(
+    key: &str,
+    name: &str,
+    label: &str,
+    weight: f64,
+    columns: &[&str],
+    derived_columns: &[&str],
+  ) -> Self {
+    Dimension {
+      key: key.to_string(),
+      name: name.to_string(),
+      label: label.to_string(),
+      weight,
+      raw_scale: default_raw_scale(),
+      columns: columns.iter().map(|s| s.to_string()).collect(),
+      derived_columns: derived_columns.iter().map(|s| s.to_string()).collect(),
+    }
+  }
+
+  /// The dimensions of the Open Mozheng Literature Cup.
+  pub fn defaults() -> Vec<Self> {
+    vec![
+      Dimension::new(
+        "literary",
+        "Literary",
+        "Lit.",
+        1.,
+        &["原始评分-文采", "literary"],
+        &["文采"],
+      ),
+      Dimension::new(
+        "thinking",
+        "Thinking",
+        "Thi.",
+        1.,
+        &["原始评分-思维", "thinking"],
+        &["思维"],
+      ),
+      Dimension::new(
+        "mozheng",
+        "Mozheng",
+        "Moz.",
+        1.5,
+        &["原始评分-魔怔", "mozheng"],
+        &["魔怔"],
+      ),
+    ]
+  }
+}
+
+/// Largest weighted sum a score normalized onto `[-1, 1]` can reach.
+pub fn max_sum(dimensions: &[Dimension]) -> f64 {
+  dimensions.iter().map(|d| d.weight.abs()).sum()
+}
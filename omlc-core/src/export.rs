@@ -0,0 +1,92 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+use crate::{
+  config::Config,
+  model::{Post, Score},
+};
+
+/// Writes one judge's scores as a CSV that [`crate::load_judge_csv`] reads
+/// back: the first accepted name of every column, and one row per score
+/// naming its post by title. Missing values are left blank and abstentions
+/// get the first abstention marker.
+pub fn write_judge_csv<W: Write>(
+  writer: W,
+  posts: &[Post],
+  scores: &[Score],
+  config: &Config,
+) -> Result<()> {
+  let first =
+    |names: &[String], fallback: &str| names.first().map_or(fallback, String::as_str).to_string();
+
+  let mut header = vec![first(&config.columns.title, "title")];
+  header.extend(config.dimensions.iter().map(|d| first(&d.columns, &d.key)));
+  header.push(first(&config.columns.comment, "comment"));
+
+  let abstained = config
+    .markers
+    .abstained
+    .first()
+    .map_or("abstain", String::as_str);
+
+  let mut writer = csv::Writer::from_writer(writer);
+  writer.write_record(&header)?;
+  for score in scores {
+    let mut record = vec![posts[score.post_id].title.clone()];
+    record.extend(score.raw.iter().map(|v| match v {
+      _ if score.abstained => abstained.to_string(),
+      Some(v) => v.to_string(),
+      None => String::new(),
+    }));
+    record.push(score.comment.clone().unwrap_or_default());
+    writer.write_record(&record)?;
+  }
+  writer.flush()?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::load::load_judge_csv;
+
+  #[test]
+  fn round_trip() {
+    let config = Config::default();
+    let posts: Vec<_> = ["Alpha", "Beta, \"quoted\"", "Gamma", "Delta"]
+      .iter()
+      .map(|title| Post {
+        id: String::new(),
+        title: title.to_string(),
+        author: "someone".to_string(),
+        url: format!("https://example.com/{}", title.len()),
+      })
+      .collect();
+    let scale = config.raw_scales("judge");
+    let scores = vec![
+      Score::new(
+        "judge".to_string(),
+        0,
+        vec![Some(7.), Some(6.5), Some(9.)],
+        scale.clone(),
+        Some("Good, \"very\" good".to_string()),
+      ),
+      Score::new(
+        "judge".to_string(),
+        1,
+        vec![Some(0.), None, Some(10.)],
+        scale.clone(),
+        None,
+      ),
+      Score::abstention("judge".to_string(), 3, scale, Some("My friend".to_string())),
+    ];
+
+    let mut csv = Vec::new();
+    write_judge_csv(&mut csv, &posts, &scores, &config).unwrap();
+    let loaded = load_judge_csv("judge", csv.as_slice(), &posts, &config).unwrap();
+
+    assert_eq!(loaded, scores);
+  }
+}
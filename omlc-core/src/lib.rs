@@ -0,0 +1,41 @@
+mod aggregate;
+mod agreement;
+mod audit;
+mod bootstrap;
+mod columns;
+mod config;
+mod conflicts;
+mod diagnostics;
+mod dimension;
+mod export;
+mod load;
+mod model;
+mod normalize;
+mod outliers;
+mod resolve;
+
+pub use aggregate::{aggregate, order, ranks, Aggregator};
+pub use agreement::{
+  correlation_matrix, icc, judges, kendall, krippendorff_alpha, pearson, spearman, units,
+  Correlation,
+};
+pub use audit::{
+  audit_judge_csv, AuditFinding, AuditSettings, DerivedColumn, Discrepancy, JudgeAudit,
+};
+pub use bootstrap::{bootstrap, Interval};
+pub use columns::{CellMarkers, ColumnAliases, ColumnMap};
+pub use config::{Config, JudgeSettings};
+pub use conflicts::{ConflictPolicy, ConflictSettings};
+pub use diagnostics::{Diagnostic, Diagnostics, Problem};
+pub use dimension::{max_sum, Dimension};
+pub use export::write_judge_csv;
+pub use load::{load_judge_csv, load_posts, validate_judge_csv};
+pub use model::{mean, median, variance, Post, PostWithScores, Score};
+pub use normalize::{
+  normalize, normalize_by_judge, Method, MinMax, Normalizer, PowerTransform, Rank, Raw, ZScore,
+};
+pub use outliers::{
+  detect as detect_outliers, robust_z, JudgeOutlier, JudgeReason, OutlierThresholds, Outliers,
+  ScoreOutlier,
+};
+pub use resolve::{normalize_title, TitleResolver};
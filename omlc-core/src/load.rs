@@ -0,0 +1,594 @@
+use std::{
+  collections::{HashMap, HashSet},
+  io::Read,
+};
+
+use anyhow::{bail, Result};
+use csv::StringRecord;
+
+use crate::{
+  columns::ColumnMap,
+  config::Config,
+  diagnostics::{Diagnostic, Diagnostics, Problem},
+  model::{Post, Score},
+  resolve::TitleResolver,
+};
+
+/// Reads the `posts.json` list of submissions, deriving missing ids.
+pub fn load_posts<R: Read>(reader: R) -> Result<Vec<Post>> {
+  let mut posts: Vec<Post> = serde_json::from_reader(reader)?;
+
+  let mut seen = HashSet::new();
+  for post in &mut posts {
+    if post.id.is_empty() {
+      post.id = Post::slug(&post.url);
+    }
+    if !seen.insert(post.id.clone()) {
+      bail!("duplicate post id `{}`", post.id);
+    }
+  }
+
+  Ok(posts)
+}
+
+/// Reads one judge's CSV, resolving every row to an entry of `posts`.
+///
+/// Fails with [`Diagnostics`] if any row is invalid. The returned scores are
+/// raw; run them through [`crate::normalize`] before comparing them with
+/// other judges.
+pub fn load_judge_csv<R: Read>(
+  owner: &str,
+  reader: R,
+  posts: &[Post],
+  config: &Config,
+) -> Result<Vec<Score>> {
+  let (scores, diagnostics) =
+    validate_judge_csv(&format!("{}.csv", owner), owner, reader, posts, config);
+  if !diagnostics.is_empty() {
+    return Err(Diagnostics(diagnostics).into());
+  }
+  Ok(scores)
+}
+
+/// Reads one judge's CSV, skipping invalid rows.
+///
+/// Columns are located by their header using `config.columns`; any others,
+/// such as the judge's own derived scores, are ignored. Returns the scores of
+/// every valid row along with a diagnostic for each problem found. `file` is
+/// only used to label the diagnostics.
+pub fn validate_judge_csv<R: Read>(
+  file: &str,
+  owner: &str,
+  mut reader: R,
+  posts: &[Post],
+  config: &Config,
+) -> (Vec<Score>, Vec<Diagnostic>) {
+  let mut diagnostics = Vec::new();
+
+  let mut bytes = Vec::new();
+  if let Err(e) = reader.read_to_end(&mut bytes) {
+    diagnostics.push(Diagnostic::file(
+      file,
+      Problem::Encoding {
+        message: e.to_string(),
+      },
+    ));
+    return (Vec::new(), diagnostics);
+  }
+  if bytes.starts_with(b"\xff\xfe") || bytes.starts_with(b"\xfe\xff") {
+    diagnostics.push(Diagnostic::file(
+      file,
+      Problem::Encoding {
+        message: "file is UTF-16, save it as UTF-8".to_string(),
+      },
+    ));
+    return (Vec::new(), diagnostics);
+  }
+
+  let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&bytes);
+
+  let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(bytes);
+  let header = match reader.headers() {
+    Ok(header) => header.clone(),
+    Err(e) => {
+      diagnostics.push(Diagnostic {
+        file: file.to_string(),
+        line: Some(1),
+        column: None,
+        problem: Problem::Encoding {
+          message: e.to_string(),
+        },
+      });
+      return (Vec::new(), diagnostics);
+    }
+  };
+  let columns = match ColumnMap::resolve(&header, &config.columns, &config.dimensions) {
+    Ok(columns) => columns,
+    Err(problems) => {
+      diagnostics.extend(problems.into_iter().map(|problem| Diagnostic {
+        file: file.to_string(),
+        line: Some(1),
+        column: None,
+        problem,
+      }));
+      return (Vec::new(), diagnostics);
+    }
+  };
+
+  let resolver = TitleResolver::new(posts);
+  let mut scores = Vec::new();
+  let mut seen = HashMap::new();
+
+  for res in reader.records() {
+    let record = match res {
+      Ok(record) => record,
+      Err(e) => {
+        let line = e.position().map(|p| line_of(bytes, p));
+        diagnostics.push(Diagnostic {
+          file: file.to_string(),
+          line,
+          column: None,
+          problem: Problem::Encoding {
+            message: e.to_string(),
+          },
+        });
+        continue;
+      }
+    };
+
+    let line = record.position().map_or(0, |p| line_of(bytes, p));
+    let mut report = |column: usize, problem: Problem| {
+      diagnostics.push(Diagnostic {
+        file: file.to_string(),
+        line: Some(line),
+        column: Some(column + 1),
+        problem,
+      });
+    };
+
+    if let Some(mut score) = parse_record(
+      owner,
+      &record,
+      &header,
+      &columns,
+      &resolver,
+      config,
+      &mut report,
+    ) {
+      if let Some(&first_line) = seen.get(&score.post_id) {
+        report(
+          columns.title,
+          Problem::DuplicateTitle {
+            title: posts[score.post_id].title.clone(),
+            first_line,
+          },
+        );
+        continue;
+      }
+      seen.insert(score.post_id, line);
+      let post = &posts[score.post_id];
+      config.conflicts.apply(&mut score, post);
+      scores.push(score);
+    }
+  }
+
+  (scores, diagnostics)
+}
+
+/// 1-based line of the file at `position`.
+///
+/// `csv` miscounts lines ending in CRLF and places a record after the `\r`
+/// ending the one before, so the line is recounted from the byte offset of
+/// the record's first character instead.
+pub(crate) fn line_of(bytes: &[u8], position: &csv::Position) -> u64 {
+  let start = (position.byte() as usize).min(bytes.len());
+  let start = start
+    + bytes[start..]
+      .iter()
+      .take_while(|&&b| b == b'\r' || b == b'\n')
+      .count();
+  bytes[..start].iter().filter(|&&b| b == b'\n').count() as u64 + 1
+}
+
+fn parse_record(
+  owner: &str,
+  record: &StringRecord,
+  header: &StringRecord,
+  columns: &ColumnMap,
+  resolver: &TitleResolver,
+  config: &Config,
+  report: &mut impl FnMut(usize, Problem),
+) -> Option<Score> {
+  let mut ok = true;
+  let missing = |column: usize, report: &mut dyn FnMut(usize, Problem)| {
+    let name = header.get(column).unwrap_or_default();
+    report(
+      column,
+      Problem::MissingColumn {
+        name: name.trim_start_matches('\u{feff}').to_string(),
+      },
+    );
+  };
+
+  let post_id = match record.get(columns.title).map(str::trim) {
+    None | Some("") => {
+      missing(columns.title, report);
+      None
+    }
+    Some(key) => match resolver.resolve(key) {
+      Ok(post_id) => Some(post_id),
+      Err(suggestions) => {
+        report(
+          columns.title,
+          Problem::UnknownTitle {
+            title: key.to_string(),
+            suggestions,
+          },
+        );
+        None
+      }
+    },
+  };
+
+  let markers = &config.markers;
+  let max = config.declared_scale(owner);
+  let mut values = vec![None; columns.values.len()];
+  let mut abstained = false;
+  for (value, &column) in values.iter_mut().zip(&columns.values) {
+    let Some(cell) = record.get(column) else {
+      missing(column, report);
+      ok = false;
+      continue;
+    };
+    if markers.is_abstention(cell) {
+      abstained = true;
+      continue;
+    }
+    if markers.is_missing(cell) {
+      continue;
+    }
+
+    match cell.trim().parse::<f64>() {
+      // Normalization absorbs the scale of judges who declare none.
+      Ok(v) if v.is_finite() && v >= 0. && max.is_none_or(|max| v <= max) => *value = Some(v),
+      Ok(v) => {
+        report(column, Problem::OutOfRange { value: v, max });
+        ok = false;
+      }
+      Err(_) => {
+        report(
+          column,
+          Problem::NonNumeric {
+            value: cell.to_string(),
+          },
+        );
+        ok = false;
+      }
+    }
+  }
+
+  let comment = columns
+    .comment
+    .and_then(|c| record.get(c))
+    .map(str::trim)
+    .filter(|c| !c.is_empty())
+    .map(str::to_string);
+
+  let post_id = post_id.filter(|_| ok)?;
+  let raw_scale = config.raw_scales(owner);
+  if abstained {
+    Some(Score::abstention(
+      owner.to_string(),
+      post_id,
+      raw_scale,
+      comment,
+    ))
+  } else if values.iter().any(Option::is_some) {
+    Some(Score::new(
+      owner.to_string(),
+      post_id,
+      values,
+      raw_scale,
+      comment,
+    ))
+  } else {
+    // A row left blank is as if the post were not listed.
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::JudgeSettings;
+
+  fn posts() -> Vec<Post> {
+    ["Alpha", "Beta", "Gamma"]
+      .iter()
+      .map(|title| Post {
+        id: title.to_lowercase(),
+        title: title.to_string(),
+        author: "someone".to_string(),
+        url: format!("https://example.com/{}", title.to_lowercase()),
+      })
+      .collect()
+  }
+
+  fn validate(csv: &str) -> (Vec<Score>, Vec<Diagnostic>) {
+    validate_judge_csv(
+      "judge.csv",
+      "judge",
+      csv.as_bytes(),
+      &posts(),
+      &Config::default(),
+    )
+  }
+
+  #[test]
+  fn post_ids() {
+    let posts = load_posts(
+      r#"[
+        { "title": "A", "author": "x", "url": "https://example.com/blog/x/Post-A/" },
+        { "id": "b", "title": "B", "author": "x", "url": "https://example.com/b.html" }
+      ]"#
+        .as_bytes(),
+    )
+    .unwrap();
+    let ids: Vec<_> = posts.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, ["post-a", "b"]);
+
+    let err = load_posts(
+      r#"[
+        { "title": "A", "author": "x", "url": "https://example.com/same.html" },
+        { "title": "B", "author": "y", "url": "https://example.org/same/" }
+      ]"#
+        .as_bytes(),
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "duplicate post id `same`");
+  }
+
+  #[test]
+  fn lines_with_either_ending() {
+    for ending in ["\n", "\r\n"] {
+      let csv = [
+        "title,原始评分-文采,原始评分-思维,原始评分-魔怔",
+        "Alpha,7,6,9",
+        "Beta,7,x,8",
+        "Delta,1,2,3",
+        "",
+      ]
+      .join(ending);
+      let (scores, diagnostics) = validate(&csv);
+      assert_eq!(scores.len(), 1);
+      let positions: Vec<_> = diagnostics.iter().map(|d| (d.line, d.column)).collect();
+      assert_eq!(
+        positions,
+        [(Some(3), Some(3)), (Some(4), Some(1))],
+        "{:?}",
+        ending
+      );
+    }
+  }
+
+  #[test]
+  fn negative_scores() {
+    let (scores, diagnostics) = validate(
+      "title,原始评分-文采,原始评分-思维,原始评分-魔怔\n\
+       Alpha,70,6,9\n\
+       Beta,10,0,-1\n",
+    );
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].raw[0], Some(70.));
+    let problems: Vec<_> = diagnostics.iter().map(|d| d.problem.clone()).collect();
+    assert_eq!(
+      problems,
+      [Problem::OutOfRange {
+        value: -1.,
+        max: None
+      }]
+    );
+  }
+
+  #[test]
+  fn declared_raw_scale() {
+    let mut config = Config::default();
+    config.judges.insert(
+      "judge".to_string(),
+      JudgeSettings {
+        raw_scale: Some(100.),
+      },
+    );
+    let (scores, diagnostics) = validate_judge_csv(
+      "judge.csv",
+      "judge",
+      "title,原始评分-文采,原始评分-思维,原始评分-魔怔\n\
+       Alpha,70,60,100\n\
+       Beta,10,0,101\n"
+        .as_bytes(),
+      &posts(),
+      &config,
+    );
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].raw_scale, [100.; 3]);
+    assert_eq!(
+      diagnostics[0].to_string(),
+      "judge.csv:3:4: score 101 is not within 0–100"
+    );
+  }
+
+  fn load(csv: &[u8]) -> Vec<Score> {
+    load_judge_csv("judge", csv, &posts(), &Config::default()).unwrap()
+  }
+
+  #[test]
+  fn bom_and_derived_columns() {
+    let scores = load(
+      "\u{feff}title,原始评分-文采,原始评分-思维,原始评分-魔怔,文采,思维,魔怔,总分\r\n\
+       Alpha,7,6,9,7,6,9,35.9\r\n\
+       Gamma,5,7,8,5,7,8,30.2\r\n"
+        .as_bytes(),
+    );
+    assert_eq!(scores.len(), 2);
+    assert_eq!(scores[0].post_id, 0);
+    assert_eq!(scores[0].raw, [Some(7.), Some(6.), Some(9.)]);
+    assert_eq!(scores[1].post_id, 2);
+    assert_eq!(scores[1].raw, [Some(5.), Some(7.), Some(8.)]);
+  }
+
+  #[test]
+  fn without_total() {
+    let scores = load(
+      "title,原始评分-文采,原始评分-思维,原始评分-魔怔,文采,思维,魔怔\n\
+       beta,3,4,5,3,4,5\n"
+        .as_bytes(),
+    );
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].post_id, 1);
+    assert_eq!(scores[0].raw, [Some(3.), Some(4.), Some(5.)]);
+    assert_eq!(scores[0].comment, None);
+  }
+
+  #[test]
+  fn with_comments() {
+    let scores = load(
+      "\"title\",\"原始评分-文采\",\"原始评分-思维\",\"原始评分-魔怔\",\"总分\",\"评论\"\n\
+       Alpha,7,6,9,35.9,\"Good, \"\"very\"\" good\"\n\
+       Beta,5,7,8,30.2,  \n"
+        .as_bytes(),
+    );
+    assert_eq!(scores.len(), 2);
+    assert_eq!(scores[0].comment.as_deref(), Some("Good, \"very\" good"));
+    assert_eq!(scores[1].comment, None);
+  }
+
+  #[test]
+  fn row_diagnostics() {
+    let (scores, diagnostics) = validate(
+      "title,原始评分-文采,原始评分-思维,原始评分-魔怔\n\
+       Alpha,7,6,9\n\
+       alpha,1,2,3\n\
+       Gama,1,2,3\n\
+       Beta,1,2\n\
+       ,1,2,3\n",
+    );
+    assert_eq!(scores.len(), 1);
+    let problems: Vec<_> = diagnostics
+      .iter()
+      .map(|d| (d.line, d.column, d.problem.clone()))
+      .collect();
+    assert_eq!(
+      problems,
+      [
+        (
+          Some(3),
+          Some(1),
+          Problem::DuplicateTitle {
+            title: "Alpha".to_string(),
+            first_line: 2,
+          }
+        ),
+        (
+          Some(4),
+          Some(1),
+          Problem::UnknownTitle {
+            title: "Gama".to_string(),
+            suggestions: vec!["Gamma".to_string()],
+          }
+        ),
+        (
+          Some(5),
+          Some(4),
+          Problem::MissingColumn {
+            name: "原始评分-魔怔".to_string(),
+          }
+        ),
+        (
+          Some(6),
+          Some(1),
+          Problem::MissingColumn {
+            name: "title".to_string(),
+          }
+        ),
+      ]
+    );
+    assert_eq!(
+      diagnostics[1].to_string(),
+      "judge.csv:4:1: post `Gama` not found; did you mean `Gamma`"
+    );
+  }
+
+  #[test]
+  fn header_diagnostics() {
+    let (scores, diagnostics) = validate("title,literary,thinking\nAlpha,1,2\n");
+    assert!(scores.is_empty());
+    assert_eq!(
+      diagnostics,
+      [Diagnostic {
+        file: "judge.csv".to_string(),
+        line: Some(1),
+        column: None,
+        problem: Problem::MissingColumn {
+          name: "原始评分-魔怔".to_string(),
+        },
+      }]
+    );
+  }
+
+  #[test]
+  fn utf16_is_rejected() {
+    let (scores, diagnostics) = validate_judge_csv(
+      "judge.csv",
+      "judge",
+      &b"\xff\xfet\0i\0"[..],
+      &posts(),
+      &Config::default(),
+    );
+    assert!(scores.is_empty());
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(diagnostics[0].problem, Problem::Encoding { .. }));
+    assert_eq!(diagnostics[0].line, None);
+  }
+
+  #[test]
+  fn strict_loading_fails_with_every_diagnostic() {
+    let err = load_judge_csv(
+      "judge",
+      "title,原始评分-文采,原始评分-思维,原始评分-魔怔\nAlpha,x,6,9\nBeta,1,2,-1\n".as_bytes(),
+      &posts(),
+      &Config::default(),
+    )
+    .unwrap_err();
+    let diagnostics = err.downcast::<Diagnostics>().unwrap();
+    assert_eq!(diagnostics.0.len(), 2);
+  }
+
+  #[test]
+  fn shipped_contest_loads_cleanly() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../contests/2023");
+    let read = |name: &str| std::fs::read(dir.join(name)).unwrap();
+    let config: Config = serde_json::from_slice(&read("contest.json")).unwrap();
+    let posts = load_posts(read("posts.json").as_slice()).unwrap();
+
+    let mut judges = 0;
+    for entry in std::fs::read_dir(dir.join("scores")).unwrap() {
+      let path = entry.unwrap().path();
+      let Some(owner) = path.file_stem().and_then(|s| s.to_str()) else {
+        continue;
+      };
+      if path.extension().is_none_or(|e| e != "csv") {
+        continue;
+      }
+      let file = path.file_name().unwrap().to_string_lossy();
+      let contents = std::fs::read(&path).unwrap();
+
+      let (mut scores, diagnostics) =
+        validate_judge_csv(&file, owner, contents.as_slice(), &posts, &config);
+      assert_eq!(diagnostics, [], "{}", file);
+      assert!(scores.len() >= 40, "{}", file);
+      crate::normalize::normalize(&mut scores, &config).unwrap();
+      judges += 1;
+    }
+    assert_eq!(judges, 12);
+  }
+}
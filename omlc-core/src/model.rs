@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{aggregate::Aggregator, dimension::Dimension};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Post {
+  /// Stable identifier used in links; derived from `url` when omitted.
+  #[serde(default)]
+  pub id: String,
+  pub title: String,
+  pub author: String,
+  pub url: String,
+}
+
+impl Post {
+  /// Derives an identifier from the last segment of `url`.
+  pub fn slug(url: &str) -> String {
+    let segment = url.trim_end_matches('/').rsplit('/').next().unwrap_or(url);
+    let segment = segment.strip_suffix(".html").unwrap_or(segment);
+
+    let mut slug = String::new();
+    for c in segment.chars() {
+      if c.is_ascii_alphanumeric() {
+        slug.push(c.to_ascii_lowercase());
+      } else if !slug.is_empty() && !slug.ends_with('-') {
+        slug.push('-');
+      }
+    }
+    slug.trim_end_matches('-').to_string()
+  }
+
+  /// Whether a judge's reference to a post, by id, URL or exact title,
+  /// designates this one.
+  pub fn is_referenced_by(&self, key: &str) -> bool {
+    key == self.id
+      || key == self.title
+      || key.trim_end_matches('/') == self.url.trim_end_matches('/')
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+  pub owner: String,
+  /// Index of the post within the list it was loaded against.
+  pub post_id: usize,
+  /// The values as the judge gave them, one per dimension, in the order of
+  /// the dimensions; `None` where the cell was blank or not applicable.
+  pub raw: Vec<Option<f64>>,
+  /// The raw scale each value was given on; see [`crate::Config::raw_scales`].
+  pub raw_scale: Vec<f64>,
+  /// The values after [`crate::normalize`]; equal to `raw` until then.
+  pub values: Vec<Option<f64>>,
+  pub comment: Option<String>,
+  /// The judge declined to score the post, e.g. for a conflict of interest.
+  /// Every value is then `None`.
+  pub abstained: bool,
+  /// The judge wrote the post; see [`crate::ConflictSettings`].
+  pub conflict: bool,
+  /// Left out of every statistic for a conflict of interest. `raw` is kept
+  /// but every value is `None`, and stays so when normalizing.
+  pub excluded: bool,
+}
+
+/// Weighted sum of `values`; `None` if any is missing.
+fn weighted_sum(values: &[Option<f64>], dimensions: &[Dimension]) -> Option<f64> {
+  values
+    .iter()
+    .zip(dimensions)
+    .map(|(v, d)| v.map(|v| v * d.weight))
+    .sum()
+}
+
+impl Score {
+  /// A score as the judge gave it on `raw_scale`, not normalized yet.
+  pub fn new(
+    owner: String,
+    post_id: usize,
+    raw: Vec<Option<f64>>,
+    raw_scale: Vec<f64>,
+    comment: Option<String>,
+  ) -> Self {
+    Score {
+      owner,
+      post_id,
+      values: raw.clone(),
+      raw,
+      raw_scale,
+      comment,
+      abstained: false,
+      conflict: false,
+      excluded: false,
+    }
+  }
+
+  /// A judge's abstention from scoring a post.
+  pub fn abstention(
+    owner: String,
+    post_id: usize,
+    raw_scale: Vec<f64>,
+    comment: Option<String>,
+  ) -> Self {
+    Score {
+      abstained: true,
+      ..Score::new(
+        owner,
+        post_id,
+        vec![None; raw_scale.len()],
+        raw_scale,
+        comment,
+      )
+    }
+  }
+
+  /// Whether every dimension was scored.
+  pub fn is_complete(&self) -> bool {
+    self.values.iter().all(Option::is_some)
+  }
+
+  /// Weighted sum of the raw values over `dimensions`; `None` if any is
+  /// missing.
+  pub fn raw_sum(&self, dimensions: &[Dimension]) -> Option<f64> {
+    weighted_sum(&self.raw, dimensions)
+  }
+
+  /// Weighted sum of the values over `dimensions`; `None` if any is missing.
+  pub fn sum(&self, dimensions: &[Dimension]) -> Option<f64> {
+    weighted_sum(&self.values, dimensions)
+  }
+}
+
+/// Arithmetic mean of `values`; NaN when there are none.
+pub fn mean(values: impl Iterator<Item = f64>) -> f64 {
+  let (sum, n) = values.fold((0., 0), |(sum, n), v| (sum + v, n + 1));
+  sum / (n as f64)
+}
+
+/// Median of `values`; NaN when there are none.
+pub fn median(values: &[f64]) -> f64 {
+  let mut sorted = values.to_vec();
+  sorted.sort_by(f64::total_cmp);
+  let n = sorted.len();
+  if n == 0 {
+    f64::NAN
+  } else if n % 2 == 1 {
+    sorted[n / 2]
+  } else {
+    (sorted[n / 2 - 1] + sorted[n / 2]) / 2.
+  }
+}
+
+/// Population variance of `values`.
+pub fn variance(values: impl Iterator<Item = f64> + Clone) -> f64 {
+  let avg = mean(values.clone());
+  mean(values.map(|v| (v - avg).powi(2)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostWithScores {
+  pub id: usize,
+  pub post: Post,
+  pub scores: Vec<Score>,
+}
+
+impl PostWithScores {
+  /// The values present on dimension `dim`.
+  fn present(&self, dim: usize) -> impl Iterator<Item = f64> + Clone + '_ {
+    self.scores.iter().filter_map(move |s| s.values[dim])
+  }
+
+  /// The weighted sums of the complete scores.
+  fn sums<'a>(&'a self, dimensions: &'a [Dimension]) -> impl Iterator<Item = f64> + Clone + 'a {
+    self.scores.iter().filter_map(|s| s.sum(dimensions))
+  }
+
+  /// Judges who scored at least one dimension of the post.
+  pub fn judge_count(&self) -> usize {
+    self
+      .scores
+      .iter()
+      .filter(|s| s.values.iter().any(Option::is_some))
+      .count()
+  }
+
+  /// Judges who abstained from scoring the post.
+  pub fn abstention_count(&self) -> usize {
+    self.scores.iter().filter(|s| s.abstained).count()
+  }
+
+  /// Scores left out for a conflict of interest.
+  pub fn excluded_count(&self) -> usize {
+    self.scores.iter().filter(|s| s.excluded).count()
+  }
+
+  /// Mean score on dimension `dim`, over the judges who gave one.
+  pub fn avg(&self, dim: usize) -> f64 {
+    mean(self.present(dim))
+  }
+
+  /// Population variance of the scores on dimension `dim`.
+  pub fn var(&self, dim: usize) -> f64 {
+    variance(self.present(dim))
+  }
+
+  /// Mean weighted sum, over the judges who scored every dimension.
+  pub fn sum_avg(&self, dimensions: &[Dimension]) -> f64 {
+    mean(self.sums(dimensions))
+  }
+
+  pub fn sum_var(&self, dimensions: &[Dimension]) -> f64 {
+    variance(self.sums(dimensions))
+  }
+
+  /// The scores on dimension `dim`, combined by `aggregator`.
+  pub fn agg(&self, dim: usize, aggregator: Aggregator) -> f64 {
+    let values: Vec<_> = self.present(dim).collect();
+    aggregator.apply(&values)
+  }
+
+  /// The weighted sums of the complete scores, combined by `aggregator`.
+  pub fn sum_agg(&self, dimensions: &[Dimension], aggregator: Aggregator) -> f64 {
+    let values: Vec<_> = self.sums(dimensions).collect();
+    aggregator.apply(&values)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn slugs() {
+    assert_eq!(
+      Post::slug("https://www.luogu.com.cn/blog/myifr/Aid0000"),
+      "aid0000"
+    );
+    assert_eq!(
+      Post::slug("https://www.luogu.com.cn/blog/zhuzhu2891/03-gu-an-suan/"),
+      "03-gu-an-suan"
+    );
+    assert_eq!(
+      Post::slug("https://example.com/posts/Hello_World.html"),
+      "hello-world"
+    );
+    assert_eq!(Post::slug("https://example.com/a/--b--c//"), "b-c");
+  }
+}
@@ -0,0 +1,461 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::Config, model::Score};
+
+/// Maps one dimension of a judge's raw scores onto a scale shared by all
+/// judges.
+pub trait Normalizer {
+  fn scale_dimension(&self, scores: Vec<f64>) -> Result<Vec<f64>>;
+}
+
+/// Stretches scores linearly onto `[-spread, spread]`, then bends them by a
+/// signed power transform whose exponent is chosen so that their mean square
+/// hits `target_variance`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PowerTransform {
+  pub spread: f64,
+  pub target_variance: f64,
+  /// Precision of the ternary search over the transform exponent.
+  pub eps: f64,
+  /// Largest tolerated distance from `target_variance` after the search.
+  pub max_error: f64,
+}
+
+impl PowerTransform {
+  pub const DEFAULT: Self = PowerTransform {
+    spread: 2. / 3.,
+    target_variance: 1. / 3.,
+    eps: 1e-6,
+    max_error: 1e-3,
+  };
+}
+
+impl Default for PowerTransform {
+  fn default() -> Self {
+    Self::DEFAULT
+  }
+}
+
+impl PowerTransform {
+  /// The exponent [`Normalizer::scale_dimension`] raises the min–max scaled
+  /// `scores` to, or `None` if they are all equal and thus left at zero.
+  pub fn exponent(&self, scores: Vec<f64>) -> Result<Option<f64>> {
+    let scores = MinMax {
+      spread: self.spread,
+    }
+    .scale_dimension(scores)?;
+    if scores.iter().all(|&x| x == 0.) {
+      return Ok(None);
+    }
+    self.fit(&scores).map(Some)
+  }
+
+  /// Searches the exponent that brings the mean square of `scores` to
+  /// `target_variance`.
+  fn fit(&self, scores: &[f64]) -> Result<f64> {
+    let PowerTransform {
+      target_variance,
+      eps,
+      max_error,
+      ..
+    } = *self;
+
+    // Ternary search
+    let mut l = 0.;
+    let mut r = 1.;
+    let mut res = 0.5;
+    while r - l > eps {
+      let one_third = (r - l) / 3.;
+      let mid_l = l + one_third;
+      let mid_r = mid_l + one_third;
+
+      let res_l = check(scores, mid_l, target_variance);
+      let res_r = check(scores, mid_r, target_variance);
+
+      if res_l < res_r {
+        res = mid_l;
+        r = mid_r;
+      } else {
+        res = mid_r;
+        l = mid_l;
+      }
+    }
+
+    let error = check(scores, res, target_variance);
+
+    if error > max_error {
+      bail!("Scaling failed: error {} is too large", error);
+    }
+
+    return Ok(exponent(res));
+
+    fn exponent(t: f64) -> f64 {
+      -f64::ln(1. - t)
+    }
+
+    fn pow_avg(scores: &[f64]) -> f64 {
+      scores.iter().map(|s| s.powi(2)).sum::<f64>() / scores.len() as f64
+    }
+
+    fn check(scores: &[f64], t: f64, target_variance: f64) -> f64 {
+      let transformed: Vec<_> = scores.iter().map(|&x| transform(x, exponent(t))).collect();
+
+      (pow_avg(&transformed) - target_variance).abs()
+    }
+  }
+}
+
+fn transform(x: f64, pow: f64) -> f64 {
+  x.signum() * x.abs().powf(pow)
+}
+
+impl Normalizer for PowerTransform {
+  fn scale_dimension(&self, scores: Vec<f64>) -> Result<Vec<f64>> {
+    let scores = MinMax {
+      spread: self.spread,
+    }
+    .scale_dimension(scores)?;
+    if scores.iter().all(|&x| x == 0.) {
+      return Ok(scores);
+    }
+
+    let pow = self.fit(&scores)?;
+    Ok(scores.into_iter().map(|x| transform(x, pow)).collect())
+  }
+}
+
+/// Stretches scores linearly onto `[-spread, spread]`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MinMax {
+  pub spread: f64,
+}
+
+impl MinMax {
+  pub const DEFAULT: Self = MinMax { spread: 2. / 3. };
+}
+
+impl Default for MinMax {
+  fn default() -> Self {
+    Self::DEFAULT
+  }
+}
+
+impl Normalizer for MinMax {
+  fn scale_dimension(&self, scores: Vec<f64>) -> Result<Vec<f64>> {
+    let min = scores.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    if min == max {
+      return Ok(scores.into_iter().map(|_| 0.).collect());
+    }
+
+    Ok(
+      scores
+        .into_iter()
+        .map(|f| ((f - min) / (max - min) * 2. - 1.) * self.spread)
+        .collect(),
+    )
+  }
+}
+
+/// Subtracts the judge's mean and divides by their standard deviation.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct ZScore;
+
+impl Normalizer for ZScore {
+  fn scale_dimension(&self, scores: Vec<f64>) -> Result<Vec<f64>> {
+    let n = scores.len() as f64;
+    let mean = scores.iter().sum::<f64>() / n;
+    let sd = (scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+    if sd == 0. {
+      return Ok(scores.into_iter().map(|_| 0.).collect());
+    }
+
+    Ok(scores.into_iter().map(|s| (s - mean) / sd).collect())
+  }
+}
+
+/// Replaces scores by their percentile within the judge's scores, mapped onto
+/// `[-1, 1]`. Ties share their average rank.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct Rank;
+
+impl Normalizer for Rank {
+  fn scale_dimension(&self, scores: Vec<f64>) -> Result<Vec<f64>> {
+    let n = scores.len();
+    if n < 2 {
+      return Ok(vec![0.; n]);
+    }
+
+    let mut order: Vec<_> = (0..n).collect();
+    order.sort_by(|&a, &b| scores[a].total_cmp(&scores[b]));
+
+    let mut res = vec![0.; n];
+    let mut i = 0;
+    while i < n {
+      let mut j = i;
+      while j + 1 < n && scores[order[j + 1]] == scores[order[i]] {
+        j += 1;
+      }
+      let rank = (i + j) as f64 / 2.;
+      for &k in &order[i..=j] {
+        res[k] = rank / (n - 1) as f64 * 2. - 1.;
+      }
+      i = j + 1;
+    }
+
+    Ok(res)
+  }
+}
+
+/// Leaves scores untouched.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct Raw;
+
+impl Normalizer for Raw {
+  fn scale_dimension(&self, scores: Vec<f64>) -> Result<Vec<f64>> {
+    Ok(scores)
+  }
+}
+
+/// The normalization selected for a contest.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "method", rename_all = "kebab-case")]
+pub enum Method {
+  Power(PowerTransform),
+  ZScore,
+  Rank,
+  MinMax(MinMax),
+  Raw,
+}
+
+impl Method {
+  /// Every method with its default parameters.
+  pub const ALL: [Method; 5] = [
+    Method::Power(PowerTransform::DEFAULT),
+    Method::ZScore,
+    Method::Rank,
+    Method::MinMax(MinMax::DEFAULT),
+    Method::Raw,
+  ];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Method::Power(_) => "power",
+      Method::ZScore => "z-score",
+      Method::Rank => "rank",
+      Method::MinMax(_) => "min-max",
+      Method::Raw => "raw",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|m| m.name() == name)
+  }
+}
+
+impl Default for Method {
+  fn default() -> Self {
+    Method::Power(PowerTransform::DEFAULT)
+  }
+}
+
+impl Normalizer for Method {
+  fn scale_dimension(&self, scores: Vec<f64>) -> Result<Vec<f64>> {
+    match self {
+      Method::Power(n) => n.scale_dimension(scores),
+      Method::ZScore => ZScore.scale_dimension(scores),
+      Method::Rank => Rank.scale_dimension(scores),
+      Method::MinMax(n) => n.scale_dimension(scores),
+      Method::Raw => Raw.scale_dimension(scores),
+    }
+  }
+}
+
+/// Normalizes one judge's raw scores into their `values`, dimension by
+/// dimension, with the method selected in `config`. Missing values and
+/// excluded scores are left out and stay missing.
+pub fn normalize(scores: &mut [Score], config: &Config) -> Result<()> {
+  for dim in 0..config.dimensions.len() {
+    let present: Vec<_> = (0..scores.len())
+      .filter(|&i| !scores[i].excluded && scores[i].raw[dim].is_some())
+      .collect();
+    if present.is_empty() {
+      continue;
+    }
+
+    let values = present.iter().filter_map(|&i| scores[i].raw[dim]).collect();
+    let values = config.normalizer.scale_dimension(values)?;
+
+    for (i, value) in present.into_iter().zip(values) {
+      scores[i].values[dim] = Some(value);
+    }
+  }
+
+  Ok(())
+}
+
+/// Normalizes the raw scores of every judge separately, keeping their order.
+pub fn normalize_by_judge(scores: &[Score], config: &Config) -> Result<Vec<Score>> {
+  let mut res = scores.to_vec();
+
+  let mut owners: Vec<_> = scores.iter().map(|s| s.owner.as_str()).collect();
+  owners.sort();
+  owners.dedup();
+
+  for owner in owners {
+    let indices: Vec<_> = (0..res.len()).filter(|&i| res[i].owner == owner).collect();
+    let mut scores: Vec<_> = indices.iter().map(|&i| res[i].clone()).collect();
+    normalize(&mut scores, config)?;
+    for (i, score) in indices.into_iter().zip(scores) {
+      res[i] = score;
+    }
+  }
+
+  Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Values of the original `Score::scale` on the same input.
+  #[test]
+  fn power_matches_original_scale() {
+    let config = Config::default();
+    let raw = [
+      [7., 6., 9.],
+      [5., 7., 8.],
+      [3., 4., 5.],
+      [8., 9., 3.],
+      [6., 2., 7.],
+      [7., 5., 9.],
+    ];
+    let expected = [
+      [
+        0.5636110601267277,
+        -0.2834062688542975,
+        -0.775901651758722,
+        0.775901651758722,
+        0.2834062688542975,
+        0.5636110601267277,
+      ],
+      [
+        0.288281951350589,
+        0.5154685491648175,
+        -0.5154685491648175,
+        0.8069605604890212,
+        -0.8069605604890212,
+        -0.2882819513505891,
+      ],
+      [
+        0.7201638016388993,
+        0.5186359011909919,
+        -0.29589644927111125,
+        -0.7201638016388993,
+        0.29589644927111114,
+        0.7201638016388993,
+      ],
+    ];
+
+    let mut scores: Vec<_> = raw
+      .iter()
+      .enumerate()
+      .map(|(post_id, values)| {
+        let values = values.iter().copied().map(Some).collect();
+        Score::new(
+          "judge".to_string(),
+          post_id,
+          values,
+          config.raw_scales("judge"),
+          None,
+        )
+      })
+      .collect();
+    normalize(&mut scores, &config).unwrap();
+
+    for (dim, expected) in expected.iter().enumerate() {
+      for (score, &expected) in scores.iter().zip(expected) {
+        let value = score.values[dim].unwrap();
+        assert!(
+          (value - expected).abs() < 1e-12,
+          "{} != {}",
+          value,
+          expected
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn missing_and_excluded_stay_out() {
+    let config = Config::default();
+    let mut scores: Vec<_> = [Some(2.), None, Some(8.), Some(10.)]
+      .into_iter()
+      .enumerate()
+      .map(|(post_id, v)| {
+        Score::new(
+          "judge".to_string(),
+          post_id,
+          vec![v, Some(5.), Some(5.)],
+          config.raw_scales("judge"),
+          None,
+        )
+      })
+      .collect();
+    scores[3].excluded = true;
+    scores[3].values = vec![None; 3];
+    normalize(&mut scores, &config).unwrap();
+
+    assert_eq!(scores[1].values[0], None);
+    assert_eq!(scores[3].values, vec![None; 3]);
+    // Only 2 and 8 are left, at either end of the scale.
+    assert!(scores[0].values[0].unwrap() < 0.);
+    assert!(scores[2].values[0].unwrap() > 0.);
+    assert_eq!(scores[0].values[1], Some(0.));
+  }
+
+  #[test]
+  fn z_score_has_unit_variance() {
+    let scaled = ZScore
+      .scale_dimension(vec![3., 7., 7., 1., 9., 4.])
+      .unwrap();
+    let n = scaled.len() as f64;
+    let mean = scaled.iter().sum::<f64>() / n;
+    let var = scaled.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+    assert!(mean.abs() < 1e-12);
+    assert!((var - 1.).abs() < 1e-12);
+
+    assert_eq!(ZScore.scale_dimension(vec![5.; 3]).unwrap(), [0.; 3]);
+  }
+
+  #[test]
+  fn rank_averages_ties() {
+    let scaled = Rank.scale_dimension(vec![4., 1., 4., 9., 2.]).unwrap();
+    // Ranks 0..=4, with the two 4s sharing (2 + 3) / 2.
+    assert_eq!(scaled, [0.25, -1., 0.25, 1., -0.5]);
+    assert!(scaled.iter().all(|s| (-1. ..=1.).contains(s)));
+
+    assert_eq!(Rank.scale_dimension(vec![3.]).unwrap(), [0.]);
+  }
+
+  #[test]
+  fn min_max_reaches_spread() {
+    let scaled = MinMax::DEFAULT
+      .scale_dimension(vec![6., 2., 10., 4.])
+      .unwrap();
+    let spread = MinMax::DEFAULT.spread;
+    assert_eq!(scaled[1], -spread);
+    assert_eq!(scaled[2], spread);
+    assert!(scaled.iter().all(|s| (-spread..=spread).contains(s)));
+
+    let scaled = MinMax { spread: 1.5 }
+      .scale_dimension(vec![0., 5.])
+      .unwrap();
+    assert_eq!(scaled, [-1.5, 1.5]);
+  }
+}
@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  config::Config,
+  dimension::Dimension,
+  model::{median, Score},
+};
+
+/// How far from the consensus a score or a judge must be to be flagged, as
+/// robust z-scores.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OutlierThresholds {
+  pub score: f64,
+  pub judge: f64,
+}
+
+impl Default for OutlierThresholds {
+  fn default() -> Self {
+    // The cut-off suggested by Iglewicz and Hoaglin.
+    OutlierThresholds {
+      score: 3.5,
+      judge: 3.5,
+    }
+  }
+}
+
+/// A score whose weighted sum is far from the other judges' sums for the same
+/// post.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreOutlier {
+  pub owner: String,
+  pub post_id: usize,
+  /// Robust z-score of the normalized sum among the post's sums.
+  pub z: f64,
+}
+
+impl ScoreOutlier {
+  pub fn describe(&self) -> String {
+    format!(
+      "Σ is {:.1} robust SDs {} the other judges'",
+      self.z.abs(),
+      if self.z > 0. { "above" } else { "below" }
+    )
+  }
+}
+
+/// Why a judge's scores as a whole stand out.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JudgeReason {
+  /// Their weighted sums are usually far from the posts' medians.
+  Deviation {
+    /// Mean absolute distance to the medians.
+    mean: f64,
+    /// Robust z-score of `mean` among the judges.
+    z: f64,
+  },
+  /// They gave every post the same raw score on a dimension, which
+  /// normalization cannot spread.
+  Constant { dimension: usize, value: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JudgeOutlier {
+  pub owner: String,
+  pub reason: JudgeReason,
+}
+
+impl JudgeOutlier {
+  pub fn describe(&self, dimensions: &[Dimension]) -> String {
+    match self.reason {
+      JudgeReason::Deviation { mean, z } => format!(
+        "usually {:.3} away from the consensus Σ, {:.1} robust SDs more than other judges",
+        mean, z
+      ),
+      JudgeReason::Constant { dimension, value } => format!(
+        "gave every post {} on {}",
+        value, dimensions[dimension].name
+      ),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Outliers {
+  pub scores: Vec<ScoreOutlier>,
+  pub judges: Vec<JudgeOutlier>,
+}
+
+impl Outliers {
+  pub fn score(&self, owner: &str, post_id: usize) -> impl Iterator<Item = &ScoreOutlier> {
+    let owner = owner.to_string();
+    self
+      .scores
+      .iter()
+      .filter(move |o| o.owner == owner && o.post_id == post_id)
+  }
+
+  pub fn judge(&self, owner: &str) -> impl Iterator<Item = &JudgeOutlier> {
+    let owner = owner.to_string();
+    self.judges.iter().filter(move |o| o.owner == owner)
+  }
+}
+
+/// Robust z-scores of `values`, based on the median and the median absolute
+/// deviation. `None` when fewer than three values are given or most are
+/// equal.
+pub fn robust_z(values: &[f64]) -> Option<Vec<f64>> {
+  if values.len() < 3 {
+    return None;
+  }
+  let med = median(values);
+  let deviations: Vec<_> = values.iter().map(|v| (v - med).abs()).collect();
+  let mad = median(&deviations);
+  if mad == 0. {
+    return None;
+  }
+  Some(values.iter().map(|v| 0.6745 * (v - med) / mad).collect())
+}
+
+/// Flags the normalized `scores` far from their post's consensus and the
+/// judges deviating from it systematically. Only complete scores have a sum
+/// to compare.
+pub fn detect(scores: &[Score], config: &Config) -> Outliers {
+  let dimensions = &config.dimensions;
+  let thresholds = config.outliers;
+  let mut outliers = Outliers::default();
+
+  let mut by_post = BTreeMap::<_, Vec<_>>::new();
+  for score in scores {
+    if let Some(sum) = score.sum(dimensions) {
+      by_post.entry(score.post_id).or_default().push((score, sum));
+    }
+  }
+
+  let mut deviations = BTreeMap::<_, Vec<_>>::new();
+  for (&post_id, post_scores) in &by_post {
+    let (post_scores, sums): (Vec<_>, Vec<_>) = post_scores.iter().copied().unzip();
+
+    let med = median(&sums);
+    for (score, sum) in post_scores.iter().zip(&sums) {
+      deviations
+        .entry(score.owner.as_str())
+        .or_default()
+        .push((sum - med).abs());
+    }
+
+    let Some(zs) = robust_z(&sums) else {
+      continue;
+    };
+    for (score, z) in post_scores.iter().zip(zs) {
+      if z.abs() > thresholds.score {
+        outliers.scores.push(ScoreOutlier {
+          owner: score.owner.clone(),
+          post_id,
+          z,
+        });
+      }
+    }
+  }
+
+  let (owners, means): (Vec<_>, Vec<_>) = deviations
+    .into_iter()
+    .map(|(owner, d)| (owner, d.iter().sum::<f64>() / d.len() as f64))
+    .unzip();
+  if let Some(zs) = robust_z(&means) {
+    for ((owner, mean), z) in owners.into_iter().zip(means).zip(zs) {
+      if z > thresholds.judge {
+        outliers.judges.push(JudgeOutlier {
+          owner: owner.to_string(),
+          reason: JudgeReason::Deviation { mean, z },
+        });
+      }
+    }
+  }
+
+  let mut by_judge = BTreeMap::<_, Vec<_>>::new();
+  for score in scores {
+    by_judge
+      .entry(score.owner.as_str())
+      .or_default()
+      .push(score);
+  }
+  for (owner, judge_scores) in by_judge {
+    for dimension in 0..dimensions.len() {
+      let values: Vec<_> = judge_scores
+        .iter()
+        .filter(|s| !s.excluded)
+        .filter_map(|s| s.raw[dimension])
+        .collect();
+      let Some((&value, rest)) = values.split_first() else {
+        continue;
+      };
+      if !rest.is_empty() && rest.iter().all(|&v| v == value) {
+        outliers.judges.push(JudgeOutlier {
+          owner: owner.to_string(),
+          reason: JudgeReason::Constant { dimension, value },
+        });
+      }
+    }
+  }
+
+  outliers
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn score(owner: &str, post_id: usize, values: [f64; 3]) -> Score {
+    let config = Config::default();
+    Score::new(
+      owner.to_string(),
+      post_id,
+      values.into_iter().map(Some).collect(),
+      config.raw_scales(owner),
+      None,
+    )
+  }
+
+  #[test]
+  fn robust_z_needs_spread() {
+    // Median 3, MAD 1.
+    let zs = robust_z(&[1., 2., 3., 4., 20.]).unwrap();
+    let expected = [-2., -1., 0., 1., 17.].map(|d| 0.6745 * d);
+    assert_eq!(zs, expected);
+    // Most values equal: the MAD is zero.
+    assert_eq!(robust_z(&[1., 1., 1., 5.]), None);
+    assert_eq!(robust_z(&[1., 5.]), None);
+  }
+
+  #[test]
+  fn planted_score() {
+    let scores = [
+      score("a", 0, [0.5; 3]),
+      score("b", 0, [0.4; 3]),
+      score("c", 0, [0.6; 3]),
+      score("d", 0, [0.5; 3]),
+      score("e", 0, [-1.; 3]),
+    ];
+    let outliers = detect(&scores, &Config::default());
+
+    assert_eq!(outliers.scores.len(), 1);
+    assert_eq!(outliers.scores[0].owner, "e");
+    assert!(outliers.scores[0].z < -3.5);
+    assert_eq!(outliers.judges.len(), 1);
+    assert_eq!(outliers.judges[0].owner, "e");
+    assert!(matches!(
+      outliers.judges[0].reason,
+      JudgeReason::Deviation { .. }
+    ));
+  }
+
+  #[test]
+  fn constant_judge() {
+    let scores = [
+      score("a", 0, [7., 3., 5.]),
+      score("a", 1, [7., 8., 6.]),
+      score("a", 2, [7., 5., 9.]),
+    ];
+    let outliers = detect(&scores, &Config::default());
+
+    assert!(outliers.scores.is_empty());
+    assert_eq!(
+      outliers.judges,
+      [JudgeOutlier {
+        owner: "a".to_string(),
+        reason: JudgeReason::Constant {
+          dimension: 0,
+          value: 7.,
+        },
+      }]
+    );
+  }
+
+  #[test]
+  fn excluded_scores_are_ignored() {
+    let mut own = score("a", 2, [1., 1., 1.]);
+    own.excluded = true;
+    own.values = vec![None; 3];
+    let scores = [
+      score("a", 0, [7., 3., 5.]),
+      score("a", 1, [7., 8., 6.]),
+      own,
+      score("b", 2, [0.5; 3]),
+      score("c", 2, [0.4; 3]),
+      score("d", 2, [0.6; 3]),
+    ];
+    let outliers = detect(&scores, &Config::default());
+
+    assert!(outliers.scores.is_empty());
+    assert_eq!(outliers.judges.len(), 1);
+    assert_eq!(outliers.judges[0].owner, "a");
+    assert!(matches!(
+      outliers.judges[0].reason,
+      JudgeReason::Constant { dimension: 0, .. }
+    ));
+  }
+}
@@ -0,0 +1,154 @@
+use crate::model::Post;
+
+/// Folds the differences judges typically introduce when typing a title by
+/// hand: full-width forms, CJK punctuation, whitespace and ASCII case.
+pub fn normalize_title(title: &str) -> String {
+  title
+    .chars()
+    .filter_map(|c| {
+      let c = match c {
+        // Full-width ASCII variants.
+        '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+        '【' | '「' | '『' | '〔' => '[',
+        '】' | '」' | '』' | '〕' => ']',
+        '“' | '”' | '„' => '"',
+        '‘' | '’' => '\'',
+        '、' => ',',
+        '。' => '.',
+        '—' | '–' => '-',
+        '〜' => '~',
+        c => c,
+      };
+      (!c.is_whitespace()).then(|| c.to_ascii_lowercase())
+    })
+    .collect()
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+  let mut prev: Vec<_> = (0..=b.len()).collect();
+  let mut cur = vec![0; b.len() + 1];
+
+  for (i, ca) in a.iter().enumerate() {
+    cur[0] = i + 1;
+    for (j, cb) in b.iter().enumerate() {
+      let replace = prev[j] + usize::from(ca != cb);
+      cur[j + 1] = replace.min(prev[j + 1] + 1).min(cur[j] + 1);
+    }
+    std::mem::swap(&mut prev, &mut cur);
+  }
+
+  prev[b.len()]
+}
+
+/// Finds the post a judge's CSV row refers to.
+pub struct TitleResolver<'a> {
+  posts: &'a [Post],
+  normalized: Vec<Vec<char>>,
+}
+
+impl<'a> TitleResolver<'a> {
+  const MAX_SUGGESTIONS: usize = 3;
+
+  pub fn new(posts: &'a [Post]) -> Self {
+    TitleResolver {
+      posts,
+      normalized: posts
+        .iter()
+        .map(|p| normalize_title(&p.title).chars().collect())
+        .collect(),
+    }
+  }
+
+  /// Resolves `key` by id, URL or title, ignoring the differences folded by
+  /// [`normalize_title`].
+  ///
+  /// On failure, returns the titles closest to `key` by edit distance.
+  pub fn resolve(&self, key: &str) -> Result<usize, Vec<String>> {
+    if let Some(id) = self.posts.iter().position(|p| p.is_referenced_by(key)) {
+      return Ok(id);
+    }
+
+    let key: Vec<_> = normalize_title(key).chars().collect();
+    if let Some(id) = self.normalized.iter().position(|t| *t == key) {
+      return Ok(id);
+    }
+
+    let mut candidates: Vec<_> = self
+      .normalized
+      .iter()
+      .enumerate()
+      .map(|(id, t)| (edit_distance(&key, t), id))
+      .filter(|&(d, id)| d <= key.len().max(self.normalized[id].len()) / 2)
+      .collect();
+    candidates.sort();
+
+    Err(
+      candidates
+        .into_iter()
+        .take(Self::MAX_SUGGESTIONS)
+        .map(|(_, id)| self.posts[id].title.clone())
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn posts() -> Vec<Post> {
+    [
+      ("aid0000", "[ID:0000] [Archive_Name:没有及格]"),
+      ("aid0002", "[ID:0002] [Archive_Name:噪音]"),
+      ("kill", "杀人煮心5"),
+      ("sport", "极限运动"),
+    ]
+    .iter()
+    .map(|(id, title)| Post {
+      id: id.to_string(),
+      title: title.to_string(),
+      author: "someone".to_string(),
+      url: format!("https://example.com/blog/{}/", id),
+    })
+    .collect()
+  }
+
+  #[test]
+  fn folds_typing_differences() {
+    assert_eq!(
+      normalize_title("【ＩＤ：0000】 Archive_Name：没有及格。"),
+      "[id:0000]archive_name:没有及格."
+    );
+  }
+
+  #[test]
+  fn resolves_by_id_url_and_loose_title() {
+    let posts = posts();
+    let resolver = TitleResolver::new(&posts);
+    assert_eq!(resolver.resolve("aid0002"), Ok(1));
+    assert_eq!(resolver.resolve("https://example.com/blog/kill"), Ok(2));
+    assert_eq!(
+      resolver.resolve("【ID:0000】 【Archive_Name:没有及格】"),
+      Ok(0)
+    );
+    assert_eq!(resolver.resolve("极限 运动"), Ok(3));
+  }
+
+  #[test]
+  fn suggests_closest_titles() {
+    let posts = posts();
+    let resolver = TitleResolver::new(&posts);
+    assert_eq!(
+      resolver.resolve("[ID:0001] [Archive_Name:噪音]"),
+      Err(vec![
+        "[ID:0002] [Archive_Name:噪音]".to_string(),
+        "[ID:0000] [Archive_Name:没有及格]".to_string(),
+      ])
+    );
+    assert_eq!(
+      resolver.resolve("杀人煮心"),
+      Err(vec!["杀人煮心5".to_string()])
+    );
+    assert_eq!(resolver.resolve("something else entirely"), Err(vec![]));
+  }
+}
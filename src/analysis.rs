@@ -0,0 +1,248 @@
+use omlc_core::{
+  correlation_matrix, icc, judges, krippendorff_alpha, units, Correlation, Dimension,
+};
+use patternfly_yew::prelude::*;
+use plotters::{
+  prelude::*,
+  style::{
+    colors::colormaps::{ColorMap, ViridisRGB},
+    text_anchor::{HPos, Pos, VPos},
+    Color,
+  },
+};
+use plotters_canvas::CanvasBackend;
+use web_sys::{HtmlCanvasElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::{app::use_contest, model::Score, text::limit_chars};
+
+/// What judges are compared on: one dimension, or the weighted sum when
+/// `None`. Judges who left it out are not compared on the post.
+fn value(dimensions: &[Dimension], dim: Option<usize>) -> impl Fn(&Score) -> Option<f64> + '_ {
+  move |s| match dim {
+    Some(i) => s.values[i],
+    None => s.sum(dimensions),
+  }
+}
+
+fn format_coefficient(c: Option<f64>) -> String {
+  c.map_or("–".to_string(), |c| format!("{:.3}", c))
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct HeatmapProps {
+  pub labels: Vec<String>,
+  /// Coefficients within `[-1, 1]`, row by row.
+  pub matrix: Vec<Vec<Option<f64>>>,
+  pub caption: String,
+}
+
+#[function_component(Heatmap)]
+pub fn heatmap(
+  HeatmapProps {
+    labels,
+    matrix,
+    caption,
+  }: &HeatmapProps,
+) -> Html {
+  let canvas = use_node_ref();
+
+  {
+    let canvas = canvas.clone();
+
+    use_effect_with(
+      (canvas, labels.clone(), matrix.clone(), caption.clone()),
+      |(canvas, labels, matrix, caption)| {
+        const LABEL_LIMIT: usize = 8;
+        // Room left of the cells for the row labels, in cells.
+        const MARGIN: f64 = 2.;
+
+        let element = canvas.cast::<HtmlCanvasElement>().unwrap();
+        let n = labels.len();
+
+        element.set_height((n * 50 + 80) as u32);
+        element.set_width(1000);
+
+        let root = CanvasBackend::with_canvas_object(element)
+          .unwrap()
+          .into_drawing_area();
+
+        root.fill(&WHITE).unwrap();
+
+        // Rows are negated so that the first one is drawn on top.
+        let mut chart = ChartBuilder::on(&root)
+          .caption(caption, ("sans-serif", 18))
+          .margin(5)
+          .build_cartesian_2d(-MARGIN..n as f64, -(n as f64)..1.)
+          .unwrap();
+
+        let label = |s: &String| limit_chars(s, LABEL_LIMIT);
+
+        let cells = || {
+          matrix.iter().enumerate().flat_map(|(row, values)| {
+            values
+              .iter()
+              .enumerate()
+              .map(move |(col, v)| (col as f64, -(row as f64), *v))
+          })
+        };
+
+        chart
+          .draw_series(cells().map(|(x, y, v)| {
+            let color = match v {
+              Some(v) => ViridisRGB.get_color_normalized(v, -1., 1.).filled(),
+              None => BLACK.mix(0.1).filled(),
+            };
+            Rectangle::new([(x, y), (x + 1., y - 1.)], color)
+          }))
+          .unwrap();
+
+        let text = TextStyle::from(("sans-serif", 12)).pos(Pos::new(HPos::Center, VPos::Center));
+        chart
+          .draw_series(cells().filter_map(|(x, y, v)| {
+            let v = v?;
+            // Viridis is dark at its low end.
+            let color = if v < 0. { WHITE } else { BLACK };
+            Some(Text::new(
+              format!("{:.2}", v),
+              (x + 0.5, y - 0.5),
+              text.color(&color),
+            ))
+          }))
+          .unwrap();
+
+        let left = TextStyle::from(("sans-serif", 12)).pos(Pos::new(HPos::Right, VPos::Center));
+        chart
+          .draw_series(
+            labels
+              .iter()
+              .enumerate()
+              .map(|(i, l)| Text::new(label(l), (-0.1, -(i as f64) - 0.5), left.clone())),
+          )
+          .unwrap();
+
+        let top = TextStyle::from(("sans-serif", 12)).pos(Pos::new(HPos::Center, VPos::Bottom));
+        chart
+          .draw_series(
+            labels
+              .iter()
+              .enumerate()
+              .map(|(i, l)| Text::new(label(l), (i as f64 + 0.5, 0.1), top.clone())),
+          )
+          .unwrap();
+
+        root.present().unwrap();
+      },
+    );
+  }
+
+  html! {
+    <canvas ref={canvas} style="width: 100%" />
+  }
+}
+
+#[function_component(Analysis)]
+pub fn analysis() -> Html {
+  let contest = use_contest();
+  let dimensions = &contest.config.dimensions;
+
+  let dim = use_state_eq(|| None::<usize>);
+  let correlation = use_state_eq(|| Correlation::Pearson);
+
+  let ondim = use_callback(dim.clone(), |e: Event, dim| {
+    let select: HtmlSelectElement = e.target_unchecked_into();
+    dim.set(select.value().parse().ok());
+  });
+  let oncorrelation = use_callback(correlation.clone(), |e: Event, correlation| {
+    let select: HtmlSelectElement = e.target_unchecked_into();
+    correlation.set(Correlation::from_name(&select.value()).unwrap_or(Correlation::Pearson));
+  });
+
+  let owners = use_memo(contest.clone(), |contest| judges(&contest.scores));
+  let matrix = use_memo(
+    (contest.clone(), *dim, *correlation),
+    |(contest, dim, correlation)| {
+      correlation_matrix(
+        &contest.scores,
+        &judges(&contest.scores),
+        *correlation,
+        value(&contest.config.dimensions, *dim),
+      )
+    },
+  );
+
+  let targets: Vec<_> = dimensions
+    .iter()
+    .enumerate()
+    .map(|(i, d)| (Some(i), d.name.clone()))
+    .chain([(None, "Σ".to_string())])
+    .collect();
+
+  let panel = targets.iter().map(|(target, name)| {
+    let by_post = units(&contest.scores, value(dimensions, *target));
+    html! {
+      <tr>
+        <td>{ name }</td>
+        <td>{ format_coefficient(krippendorff_alpha(&by_post)) }</td>
+        <td>{ format_coefficient(icc(&by_post)) }</td>
+      </tr>
+    }
+  });
+
+  let name = targets
+    .iter()
+    .find(|(target, _)| target == &*dim)
+    .map(|(_, name)| name.clone())
+    .unwrap_or_default();
+
+  html! {
+    <>
+      <Title level={Level::H2}>{ "Panel agreement" }</Title>
+      <p>
+        { "Computed on the normalized scores. Krippendorff's α uses the interval metric; \
+           the ICC is the one-way random-effects ICC(1)." }
+      </p>
+      <table class="pf-v5-c-table pf-m-compact" role="grid">
+        <thead>
+          <tr>
+            <th>{ "Dimension" }</th>
+            <th>{ "Krippendorff's α" }</th>
+            <th>{ "ICC(1)" }</th>
+          </tr>
+        </thead>
+        <tbody>
+          { for panel }
+        </tbody>
+      </table>
+      <Title level={Level::H2}>{ "Pairwise agreement" }</Title>
+      <Toolbar>
+        <ToolbarContent>
+          <ToolbarItem>
+            <select class="pf-v5-c-form-control" onchange={ondim}>
+              { for targets.iter().map(|(target, name)| html! {
+                <option
+                  value={target.map(|i| i.to_string()).unwrap_or_default()}
+                  selected={target == &*dim}
+                >
+                  { name }
+                </option>
+              }) }
+            </select>
+          </ToolbarItem>
+          <ToolbarItem>
+            <select class="pf-v5-c-form-control" onchange={oncorrelation}>
+              { for Correlation::ALL.iter().map(|c| html! {
+                <option value={c.name()} selected={c == &*correlation}>{ c.name() }</option>
+              }) }
+            </select>
+          </ToolbarItem>
+        </ToolbarContent>
+      </Toolbar>
+      <Heatmap
+        labels={(*owners).clone()}
+        matrix={(*matrix).clone()}
+        caption={format!("{} correlation on {}, over the posts both judges scored", correlation.name(), name)}
+      />
+    </>
+  }
+}
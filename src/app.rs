@@ -1,11 +1,53 @@
+use std::rc::Rc;
+
+use gloo::{
+  history::{BrowserHistory, History},
+  timers::callback::Interval,
+};
 use patternfly_yew::prelude::*;
-use yew::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use web_sys::HtmlSelectElement;
+use yew::{platform::spawn_local, prelude::*};
 use yew_nested_router::prelude::{Switch as RouterSwitch, *};
 
-use crate::{board::Board, post::Post, status::Status};
+use crate::{
+  analysis::Analysis,
+  audit::Audit,
+  author::Author,
+  board::Board,
+  compare::Compare,
+  entry::ScoreEntry,
+  judge::Judge,
+  model::{embedded_contests, fetch_contests, Changes, Contest, Score},
+  post::Post,
+  problems::Problems,
+  ranking::Ranking,
+  status::Status,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Target)]
 pub enum AppRoute {
+  #[target(index)]
+  Index,
+  #[target(rename = "c")]
+  Contest {
+    slug: String,
+    #[target(nested)]
+    target: ContestRoute,
+  },
+}
+
+impl AppRoute {
+  pub fn contest(contest: &Contest, target: ContestRoute) -> Self {
+    AppRoute::Contest {
+      slug: contest.slug.clone(),
+      target,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Target)]
+pub enum ContestRoute {
   #[target(index)]
   Home,
   #[target(rename = "b")]
@@ -13,18 +55,313 @@ pub enum AppRoute {
   #[target(rename = "s")]
   Status,
   #[target(rename = "p")]
-  Post { id: usize },
+  Post { id: String },
+  #[target(rename = "d")]
+  Problems,
+  #[target(rename = "c")]
+  Compare,
+  #[target(rename = "e")]
+  Entry,
+  #[target(rename = "j")]
+  Judge { owner: String },
+  #[target(rename = "a")]
+  Author { name: String },
+  #[target(rename = "g")]
+  Analysis,
+  #[target(rename = "r")]
+  Ranking,
+  #[target(rename = "u")]
+  Audit,
+}
+
+/// How often live reload fetches the data again, in milliseconds.
+const REFRESH_INTERVAL: u32 = 30_000;
+
+/// Every contest, as loaded by [`App`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Data {
+  pub contests: Vec<Rc<Contest>>,
+  /// Why the embedded data is shown instead of the served one.
+  pub fallback: Option<String>,
+  /// Why the last live reload failed.
+  pub refresh_error: Option<String>,
+}
+
+impl Data {
+  /// Replaces the contests by freshly fetched ones, recording what changed.
+  fn refreshed(&self, contests: Vec<Contest>) -> Self {
+    let contests = contests
+      .into_iter()
+      .map(
+        |mut contest| match self.contests.iter().find(|c| c.slug == contest.slug) {
+          Some(old) => {
+            contest.changes = Changes::between(old, &contest);
+            if **old == contest {
+              old.clone()
+            } else {
+              Rc::new(contest)
+            }
+          }
+          None => Rc::new(contest),
+        },
+      )
+      .collect();
+
+    Data {
+      contests,
+      fallback: None,
+      refresh_error: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Loading {
+  Pending,
+  Done(Data),
+  Failed(String),
+}
+
+enum Action {
+  Loaded(Loading),
+  Refreshed(Result<Vec<Contest>, String>),
+}
+
+impl Reducible for Loading {
+  type Action = Action;
+
+  fn reduce(self: Rc<Self>, action: Action) -> Rc<Self> {
+    match (action, &*self) {
+      (Action::Loaded(loading), _) => Rc::new(loading),
+      (Action::Refreshed(Ok(contests)), Loading::Done(data)) if !contests.is_empty() => {
+        Rc::new(Loading::Done(data.refreshed(contests)))
+      }
+      (Action::Refreshed(Err(e)), Loading::Done(data)) => Rc::new(Loading::Done(Data {
+        refresh_error: Some(e),
+        ..data.clone()
+      })),
+      _ => self,
+    }
+  }
+}
+
+async fn load() -> Loading {
+  let fetched = fetch_contests().await;
+  let (contests, fallback) = match fetched {
+    Ok(contests) => (Ok(contests), None),
+    Err(e) => (embedded_contests(), Some(format!("{:#}", e))),
+  };
+
+  match contests {
+    Ok(contests) if !contests.is_empty() => Loading::Done(Data {
+      contests: contests.into_iter().map(Rc::new).collect(),
+      fallback,
+      refresh_error: None,
+    }),
+    Ok(_) => Loading::Failed("No contest is listed in `contests/contests.json`.".to_string()),
+    Err(e) => Loading::Failed(format!("{:#}", e)),
+  }
+}
+
+/// Whether the data is fetched again periodically.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiveReload {
+  pub enabled: bool,
+  pub onchange: Callback<bool>,
+}
+
+/// Marks a row whose scores were changed by the last live reload.
+#[function_component(UpdatedLabel)]
+pub fn updated_label() -> Html {
+  html! {
+    <>
+      { " " }
+      <Label compact=true color={Color::Orange} label="Updated" />
+    </>
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct OutlierLabelProps {
+  pub reasons: Vec<String>,
+}
+
+/// Marks a score standing out from the other judges', explaining why on hover.
+#[function_component(OutlierLabel)]
+pub fn outlier_label(OutlierLabelProps { reasons }: &OutlierLabelProps) -> Html {
+  html! {
+    <>
+      { " " }
+      <Tooltip text={reasons.join("; ")}>
+        <Label compact=true color={Color::Red} label="Outlier" />
+      </Tooltip>
+    </>
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct ConflictLabelProps {
+  pub score: Score,
+}
+
+/// Marks a judge's score of their own post.
+#[function_component(ConflictLabel)]
+pub fn conflict_label(ConflictLabelProps { score }: &ConflictLabelProps) -> Html {
+  let text = if score.excluded {
+    format!(
+      "{} wrote this post; the score is left out of every statistic",
+      score.owner
+    )
+  } else {
+    format!(
+      "{} wrote this post; the score is counted nonetheless",
+      score.owner
+    )
+  };
+  html! {
+    <>
+      { " " }
+      <Tooltip {text}>
+        <Label compact=true color={Color::Purple} label={if score.excluded { "Excluded" } else { "Own post" }} />
+      </Tooltip>
+    </>
+  }
+}
+
+#[hook]
+pub fn use_data() -> Data {
+  use_context::<Data>().expect("no data in context")
+}
+
+#[hook]
+pub fn use_live_reload() -> LiveReload {
+  use_context::<LiveReload>().expect("no live reload in context")
+}
+
+/// Page state kept in the query string of the URL, so that links to the page
+/// preserve it. Query strings that do not parse yield the default.
+#[hook]
+pub fn use_query<T>() -> (T, Callback<T>)
+where
+  T: Serialize + DeserializeOwned + Default + Clone + PartialEq + 'static,
+{
+  let state = use_state_eq(|| {
+    BrowserHistory::new()
+      .location()
+      .query::<T>()
+      .unwrap_or_default()
+  });
+
+  let onchange = use_callback(state.clone(), |value: T, state| {
+    let history = BrowserHistory::new();
+    let path = history.location().path().to_string();
+    // The state is still applied to the page if the URL cannot be updated.
+    let _ = history.replace_with_query(path, &value);
+    state.set(value);
+  });
+
+  ((*state).clone(), onchange)
+}
+
+/// The contest of the current route.
+#[hook]
+pub fn use_contest() -> Rc<Contest> {
+  use_context::<Rc<Contest>>().expect("no contest in context")
+}
+
+pub fn switch_app_route(route: AppRoute) -> Html {
+  html! { <ContestSwitch {route} /> }
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+struct ContestSwitchProps {
+  route: AppRoute,
 }
 
-pub fn switch_app_route(routes: AppRoute) -> Html {
-  let inner = match routes {
-    AppRoute::Home => html! { <h1>{ "Home" }</h1> },
-    AppRoute::Board => html! { <Board /> },
-    AppRoute::Status => html! { <Status /> },
-    AppRoute::Post { id } => html! { <Post post_id={id} /> },
+#[function_component(ContestSwitch)]
+fn contest_switch(ContestSwitchProps { route }: &ContestSwitchProps) -> Html {
+  let data = use_data();
+  let first = data.contests[0].clone();
+
+  let (contest, target) = match route.clone() {
+    AppRoute::Index => (first, ContestRoute::Home),
+    AppRoute::Contest { slug, target } => match data.contests.iter().find(|c| c.slug == slug) {
+      Some(contest) => (contest.clone(), target),
+      None => {
+        return html! {
+          <ContextProvider<Rc<Contest>> context={first}>
+            <AppPage>
+              <EmptyState title="Contest not found" icon={Icon::Search}>
+                { format!("No contest has the slug `{}`.", slug) }
+              </EmptyState>
+            </AppPage>
+          </ContextProvider<Rc<Contest>>>
+        };
+      }
+    },
+  };
+
+  let inner = match target {
+    ContestRoute::Home => html! { <h1>{ "Home" }</h1> },
+    ContestRoute::Board => html! { <Board /> },
+    ContestRoute::Status => html! { <Status /> },
+    ContestRoute::Post { id } => html! { <Post post_id={id} /> },
+    ContestRoute::Problems => html! { <Problems /> },
+    ContestRoute::Compare => html! { <Compare /> },
+    ContestRoute::Entry => html! { <ScoreEntry /> },
+    ContestRoute::Judge { owner } => html! { <Judge {owner} /> },
+    ContestRoute::Author { name } => html! { <Author {name} /> },
+    ContestRoute::Analysis => html! { <Analysis /> },
+    ContestRoute::Ranking => html! { <Ranking /> },
+    ContestRoute::Audit => html! { <Audit /> },
   };
 
-  html! { <AppPage>{inner}</AppPage>}
+  // Keyed by contest so that pages start afresh when switching contests.
+  html! {
+    <ContextProvider<Rc<Contest>> context={contest.clone()}>
+      <AppPage>
+        <div key={contest.slug.clone()}>{inner}</div>
+      </AppPage>
+    </ContextProvider<Rc<Contest>>>
+  }
+}
+
+#[function_component(ContestSelect)]
+fn contest_select() -> Html {
+  let router = use_router::<AppRoute>();
+  let data = use_data();
+  let contest = use_contest();
+
+  let onchange = Callback::from(move |e: Event| {
+    let select: HtmlSelectElement = e.target_unchecked_into();
+    if let Some(router) = &router {
+      // Posts, judges and authors are per contest, every other page exists
+      // everywhere.
+      let target = match router.active() {
+        Some(AppRoute::Contest { target, .. })
+          if !matches!(
+            target,
+            ContestRoute::Post { .. } | ContestRoute::Judge { .. } | ContestRoute::Author { .. }
+          ) =>
+        {
+          target.clone()
+        }
+        _ => ContestRoute::Home,
+      };
+      router.push(AppRoute::Contest {
+        slug: select.value(),
+        target,
+      });
+    }
+  });
+
+  html! {
+    <select class="pf-v5-c-form-control" {onchange}>
+      { for data.contests.iter().map(|c| html! {
+        <option value={c.slug.clone()} selected={c.slug == contest.slug}>{ &c.name }</option>
+      }) }
+    </select>
+  }
 }
 
 #[derive(Clone, Debug, PartialEq, Properties)]
@@ -34,22 +371,45 @@ pub struct PageProps {
 
 #[function_component(AppPage)]
 fn page(props: &PageProps) -> Html {
+  let data = use_data();
+  let live = use_live_reload();
+  let contest = use_contest();
+  let to = |target| AppRoute::contest(&contest, target);
+
   let sidebar = html_nested! {
     <PageSidebar>
+      <ContestSelect />
+      <Switch label="Live reload" checked={live.enabled} onchange={live.onchange.clone()} />
       <Nav>
         <NavList>
-          <NavRouterItem<AppRoute> to={AppRoute::Home}>{"Home"}</NavRouterItem<AppRoute>>
-          <NavRouterItem<AppRoute> to={AppRoute::Board}>{"Board"}</NavRouterItem<AppRoute>>
-          <NavRouterItem<AppRoute> to={AppRoute::Status}>{"Status"}</NavRouterItem<AppRoute>>
+          <NavRouterItem<AppRoute> to={to(ContestRoute::Home)}>{"Home"}</NavRouterItem<AppRoute>>
+          <NavRouterItem<AppRoute> to={to(ContestRoute::Board)}>{"Board"}</NavRouterItem<AppRoute>>
+          <NavRouterItem<AppRoute> to={to(ContestRoute::Ranking)}>{"Ranking"}</NavRouterItem<AppRoute>>
+          <NavRouterItem<AppRoute> to={to(ContestRoute::Status)}>{"Status"}</NavRouterItem<AppRoute>>
+          <NavRouterItem<AppRoute> to={to(ContestRoute::Compare)}>{"Compare"}</NavRouterItem<AppRoute>>
+          <NavRouterItem<AppRoute> to={to(ContestRoute::Analysis)}>{"Analysis"}</NavRouterItem<AppRoute>>
+          <NavRouterItem<AppRoute> to={to(ContestRoute::Problems)}>{"Data problems"}</NavRouterItem<AppRoute>>
+          <NavRouterItem<AppRoute> to={to(ContestRoute::Audit)}>{"Audit"}</NavRouterItem<AppRoute>>
+          <NavRouterItem<AppRoute> to={to(ContestRoute::Entry)}>{"Score entry"}</NavRouterItem<AppRoute>>
         </NavList>
       </Nav>
     </PageSidebar>
   };
 
-  let brand = html_nested! {"Open Mozheng Literature Cup"};
+  let brand = html! { &contest.name };
 
   html! {
     <Page {brand} {sidebar}>
+      if let Some(reason) = &data.fallback {
+        <Alert inline=true r#type={AlertType::Warning} title="Showing built-in data">
+          { format!("The served data could not be loaded: {}", reason) }
+        </Alert>
+      }
+      if let Some(reason) = &data.refresh_error {
+        <Alert inline=true r#type={AlertType::Warning} title="Live reload failed">
+          { reason }
+        </Alert>
+      }
       { for props.children.iter() }
     </Page>
   }
@@ -57,9 +417,58 @@ fn page(props: &PageProps) -> Html {
 
 #[function_component(App)]
 pub fn app() -> Html {
-  html! {
-    <Router<AppRoute>>
-      <RouterSwitch<AppRoute> render={switch_app_route} />
-    </Router<AppRoute>>
+  let loading = use_reducer_eq(|| Loading::Pending);
+  let live = use_state_eq(|| false);
+
+  {
+    let dispatcher = loading.dispatcher();
+    use_effect_with((), move |_| {
+      spawn_local(async move { dispatcher.dispatch(Action::Loaded(load().await)) });
+    });
+  }
+
+  {
+    let dispatcher = loading.dispatcher();
+    use_effect_with(*live, move |live| {
+      let interval = live.then(|| {
+        Interval::new(REFRESH_INTERVAL, move || {
+          let dispatcher = dispatcher.clone();
+          spawn_local(async move {
+            let contests = fetch_contests().await.map_err(|e| format!("{:#}", e));
+            dispatcher.dispatch(Action::Refreshed(contests));
+          });
+        })
+      });
+      move || drop(interval)
+    });
+  }
+
+  let live = LiveReload {
+    enabled: *live,
+    onchange: use_callback(live.clone(), |enabled, live| live.set(enabled)),
+  };
+
+  match &*loading {
+    Loading::Pending => html! {
+      <Bullseye>
+        <Spinner />
+      </Bullseye>
+    },
+    Loading::Failed(e) => html! {
+      <Bullseye>
+        <EmptyState title="Cannot load the contests" icon={Icon::ExclamationCircle}>
+          { e }
+        </EmptyState>
+      </Bullseye>
+    },
+    Loading::Done(data) => html! {
+      <ContextProvider<Data> context={data.clone()}>
+        <ContextProvider<LiveReload> context={live}>
+          <Router<AppRoute>>
+            <RouterSwitch<AppRoute> render={switch_app_route} />
+          </Router<AppRoute>>
+        </ContextProvider<LiveReload>>
+      </ContextProvider<Data>>
+    },
   }
 }
@@ -0,0 +1,165 @@
+use std::rc::Rc;
+
+use omlc_core::AuditFinding;
+use patternfly_yew::prelude::*;
+use yew::prelude::*;
+use yew_nested_router::components::Link;
+
+use crate::{
+  app::{use_contest, AppRoute, ContestRoute},
+  model::Contest,
+};
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Columns {
+  Owner,
+  Line,
+  Post,
+  Column,
+  Problem,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Entry {
+  contest: Rc<Contest>,
+  owner: String,
+  finding: AuditFinding,
+}
+
+impl TableEntryRenderer<Columns> for Entry {
+  fn render_cell(&self, ctx: CellContext<Columns>) -> Cell {
+    let Entry {
+      contest,
+      owner,
+      finding,
+    } = self;
+    let post = &contest.posts[finding.post_id];
+    match ctx.column {
+      Columns::Owner => html! {
+        <Link<AppRoute> target={AppRoute::contest(contest, ContestRoute::Judge { owner: owner.clone() })}>
+          { owner }
+        </Link<AppRoute>>
+      },
+      Columns::Line => html! { finding.line.to_string() },
+      Columns::Post => html! {
+        <Link<AppRoute> target={AppRoute::contest(contest, ContestRoute::Post { id: post.id.clone() })}>
+          { &post.title }
+        </Link<AppRoute>>
+      },
+      Columns::Column => html! { finding.column.name(&contest.config.dimensions) },
+      Columns::Problem => html! { finding.describe() },
+    }
+    .into()
+  }
+}
+
+#[function_component(Audit)]
+pub fn audit() -> Html {
+  let contest = use_contest();
+  let dimensions = &contest.config.dimensions;
+
+  let entries = use_state_eq(Vec::new);
+  {
+    let entries = entries.clone();
+    use_effect_with(contest.clone(), move |contest| {
+      let p: Vec<_> = contest
+        .audits
+        .iter()
+        .flat_map(|audit| {
+          audit.findings.iter().map(|finding| Entry {
+            contest: contest.clone(),
+            owner: audit.owner.clone(),
+            finding: finding.clone(),
+          })
+        })
+        .collect();
+      entries.set(p);
+    });
+  }
+
+  let (entries, _) = use_table_data(UseStateTableModel::new(entries));
+
+  let findings: usize = contest.audits.iter().map(|a| a.findings.len()).sum();
+  let mismatches = contest
+    .audits
+    .iter()
+    .filter(|a| a.mismatched_weights.is_some())
+    .count();
+
+  let summary = contest.audits.iter().map(|audit| {
+    let columns: Vec<_> = audit.columns.iter().map(|c| c.name(dimensions)).collect();
+    html! {
+      <tr>
+        <td>
+          <Link<AppRoute> target={AppRoute::contest(&contest, ContestRoute::Judge { owner: audit.owner.clone() })}>
+            { &audit.owner }
+          </Link<AppRoute>>
+        </td>
+        <td>{ columns.join(", ") }</td>
+        <td>{ audit.total_formula(dimensions).unwrap_or_default() }</td>
+        <td>{ audit.total_correlation.map(|rho| format!("{:.3}", rho)).unwrap_or_default() }</td>
+        <td>{ audit.describe_weights(dimensions).unwrap_or_default() }</td>
+        <td>{ audit.findings.len().to_string() }</td>
+      </tr>
+    }
+  });
+
+  let header = html_nested! {
+    <TableHeader<Columns>>
+      <TableColumn<Columns> label="Judge" index={Columns::Owner} />
+      <TableColumn<Columns> label="Line" index={Columns::Line} />
+      <TableColumn<Columns> label="Post" index={Columns::Post} />
+      <TableColumn<Columns> label="Column" index={Columns::Column} />
+      <TableColumn<Columns> label="Problem" index={Columns::Problem} />
+    </TableHeader<Columns>>
+  };
+
+  html! {
+    <>
+      <p>
+        { "Judges' spreadsheets derive a rescaled score per dimension and a total from their \
+           raw scores. Each derived score is normalized like the raw one and must land on \
+           the same value; the total must follow the same formula on every row, weighing \
+           the dimensions roughly as the contest does. The spreadsheets normalize \
+           differently from this site, so their totals are only compared with our Σ by \
+           rank." }
+      </p>
+      <table class="pf-v5-c-table pf-m-compact" role="grid">
+        <thead>
+          <tr>
+            <th>{ "Judge" }</th>
+            <th>{ "Derived columns" }</th>
+            <th>{ "Total" }</th>
+            <th>{ "Spearman with Σ" }</th>
+            <th>{ "Weights" }</th>
+            <th>{ "Disagreements" }</th>
+          </tr>
+        </thead>
+        <tbody>
+          { for summary }
+        </tbody>
+      </table>
+      if mismatches > 0 {
+        <Alert inline=true r#type={AlertType::Warning} title={format!("{} judges' totals disagree with the weights", mismatches)}>
+          { "Check the total formulas of their spreadsheets before publishing." }
+        </Alert>
+      }
+      if findings == 0 {
+        <EmptyState title="No disagreements" icon={Icon::CheckCircle}>
+          { format!(
+            "Every derived value is within {}% of its column's range of ours.",
+            contest.config.audit.tolerance * 100.
+          ) }
+        </EmptyState>
+      } else {
+        <Alert inline=true r#type={AlertType::Warning} title={format!("{} derived values disagree", findings)}>
+          { "Check the spreadsheet formulas of these rows before publishing." }
+        </Alert>
+        <Table<Columns, UseTableData<Columns, UseStateTableModel<Entry>>>
+          {header}
+          {entries}
+        />
+      }
+    </>
+  }
+}
@@ -0,0 +1,192 @@
+use std::rc::Rc;
+
+use omlc_core::{mean, ranks};
+use patternfly_yew::prelude::*;
+use yew::prelude::*;
+use yew_nested_router::components::Link;
+
+use crate::{
+  app::{use_contest, AppRoute, ContestRoute, UpdatedLabel},
+  model::{Contest, PostWithScores},
+};
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Columns {
+  Title,
+  SumAvg,
+  Rank,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Entry {
+  contest: Rc<Contest>,
+  post: PostWithScores,
+  /// Rank of the post's Σ average among every post of the contest.
+  rank: usize,
+}
+
+impl TableEntryRenderer<Columns> for Entry {
+  fn render_cell(&self, ctx: CellContext<Columns>) -> Cell {
+    let Entry {
+      contest,
+      post,
+      rank,
+    } = self;
+    let dimensions = &contest.config.dimensions;
+    match ctx.column {
+      Columns::Title => html! {
+        <>
+          <Link<AppRoute> target={AppRoute::contest(contest, ContestRoute::Post { id: post.post.id.clone() })}>
+            { &post.post.title }
+          </Link<AppRoute>>
+          if contest.changes.post(&post.post.id) {
+            <UpdatedLabel />
+          }
+        </>
+      },
+      Columns::SumAvg => html!(
+        <Tooltip text={format!("Sum Average: {:.9}", post.sum_avg(dimensions))}>
+          {format!("{:.3}", post.sum_avg(dimensions))}
+        </Tooltip>
+      ),
+      Columns::Rank => html! { format!("{} / {}", rank, contest.posts.len()) },
+    }
+    .into()
+  }
+}
+
+fn sort(entries: &mut [Entry], column: Columns, asc: bool, contest: &Contest) {
+  let dimensions = &contest.config.dimensions;
+
+  match column {
+    Columns::Title => {
+      entries.sort_by(|a, b| a.post.post.title.cmp(&b.post.post.title));
+    }
+    Columns::SumAvg => {
+      entries.sort_by(|a, b| {
+        a.post
+          .sum_avg(dimensions)
+          .total_cmp(&b.post.sum_avg(dimensions))
+      });
+    }
+    Columns::Rank => {
+      entries.sort_by_key(|e| e.rank);
+    }
+  };
+
+  if !asc {
+    entries.reverse();
+  }
+}
+
+/// Every post of `author` with its rank in the contest.
+fn author_posts(contest: &Contest, author: &str) -> Vec<(PostWithScores, usize)> {
+  let dimensions = &contest.config.dimensions;
+  let sums: Vec<_> = contest
+    .posts_with_scores
+    .iter()
+    .map(|p| p.sum_avg(dimensions))
+    .collect();
+
+  contest
+    .posts_with_scores
+    .iter()
+    .zip(ranks(&sums))
+    .filter(|(p, _)| p.post.author == author)
+    .map(|(p, rank)| (p.clone(), rank))
+    .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct AuthorProps {
+  pub name: String,
+}
+
+#[function_component(Author)]
+pub fn author(AuthorProps { name }: &AuthorProps) -> Html {
+  let contest = use_contest();
+  let sort_by = use_state_eq(|| (Columns::Rank, true));
+
+  // Rebuilt whenever the contest is reloaded, keeping the chosen order.
+  let entries = use_state_eq(Vec::new);
+  {
+    let entries = entries.clone();
+    use_effect_with(
+      (contest.clone(), name.clone(), *sort_by),
+      move |(contest, name, (column, asc))| {
+        let mut p: Vec<_> = author_posts(contest, name)
+          .into_iter()
+          .map(|(post, rank)| Entry {
+            contest: contest.clone(),
+            post,
+            rank,
+          })
+          .collect();
+        sort(&mut p, *column, *asc, contest);
+        entries.set(p);
+      },
+    );
+  }
+
+  let on_sort_by = Some(use_callback(
+    sort_by.clone(),
+    |val: TableHeaderSortBy<Columns>, sort_by| sort_by.set((val.index, val.asc)),
+  ));
+
+  let posts = author_posts(&contest, name);
+  let (entries, _) = use_table_data(UseStateTableModel::new(entries));
+
+  if posts.is_empty() {
+    return html! {
+      <EmptyState title="Author not found" icon={Icon::Search}>
+        { format!("No post is by `{}`.", name) }
+      </EmptyState>
+    };
+  }
+
+  let dimensions = &contest.config.dimensions;
+  let sums: Vec<_> = posts.iter().map(|(p, _)| p.sum_avg(dimensions)).collect();
+  let best = sums.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+  let best_rank = posts
+    .iter()
+    .map(|(_, rank)| *rank)
+    .min()
+    .unwrap_or_default();
+
+  let header = html_nested! {
+    <TableHeader<Columns>>
+      <TableColumn<Columns> label="Title" index={Columns::Title} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label="Σ Avg" index={Columns::SumAvg} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label="Rank" index={Columns::Rank} onsort={on_sort_by.clone()} />
+    </TableHeader<Columns>>
+  };
+
+  html! {
+    <>
+      <Title level={Level::H1}>
+        { name }
+        <sub>{ format!("{} posts", posts.len()) }</sub>
+      </Title>
+      <table class="pf-v5-c-table pf-m-compact" role="grid">
+        <thead>
+          <tr>
+            <th>{ "Best Σ Avg" }</th>
+            <th>{ "Best rank" }</th>
+            <th>{ "Mean Σ Avg" }</th>
+          </tr>
+        </thead>
+        <tbody>
+          <tr>
+            <td>{ format!("{:.3}", best) }</td>
+            <td>{ format!("{} / {}", best_rank, contest.posts.len()) }</td>
+            <td>{ format!("{:.3}", mean(sums.into_iter())) }</td>
+          </tr>
+        </tbody>
+      </table>
+      <Table<Columns, UseTableData<Columns, UseStateTableModel<Entry>>>
+        {header}
+        {entries}
+      />
+    </>
+  }
+}
@@ -1,24 +1,67 @@
+use std::{cmp::Ordering, rc::Rc};
+
+use omlc_core::{judges, order, Aggregator, Interval};
 use patternfly_yew::prelude::*;
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlSelectElement;
 use yew::prelude::*;
 use yew_nested_router::components::Link;
 
 use crate::{
-  app::AppRoute,
-  model::{PostWithScores, POSTS_WITH_SCORES},
+  app::{use_contest, use_query, AppRoute, ContestRoute, UpdatedLabel},
+  filter::{FilterQuery, FilterToolbar},
+  model::{Contest, PostWithScores},
 };
 
+/// Board settings kept in the URL.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoardQuery {
+  #[serde(default)]
+  pub aggregator: Aggregator,
+  #[serde(flatten)]
+  pub filters: FilterQuery,
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct AggregatorSelectProps {
+  pub value: Aggregator,
+  pub onchange: Callback<Aggregator>,
+}
+
+#[function_component(AggregatorSelect)]
+pub fn aggregator_select(
+  AggregatorSelectProps { value, onchange }: &AggregatorSelectProps,
+) -> Html {
+  let onchange = onchange.reform(|e: Event| {
+    let select: HtmlSelectElement = e.target_unchecked_into();
+    select.value().parse().unwrap_or_default()
+  });
+
+  // One given in the URL may not be among the common ones.
+  let mut aggregators = Aggregator::COMMON.to_vec();
+  if !aggregators.contains(value) {
+    aggregators.push(*value);
+  }
+
+  html! {
+    <select class="pf-v5-c-form-control" {onchange}>
+      { for aggregators.iter().map(|a| html! {
+        <option value={a.to_string()} selected={a == value}>{ a.to_string() }</option>
+      }) }
+    </select>
+  }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum Columns {
   Title,
   Author,
-  LiteraryAvg,
-  LiteraryVar,
-  ThinkingAvg,
-  ThinkingVar,
-  MozhengAvg,
-  MozhengVar,
+  Avg(usize),
+  Var(usize),
   SumAvg,
   SumVar,
+  Coverage,
+  RankRange,
   Open,
 }
 
@@ -32,140 +75,259 @@ fn limit_string(s: &str, limit: usize) -> String {
 
 const TITLE_LIMIT: usize = 16;
 
-impl TableEntryRenderer<Columns> for PostWithScores {
+#[derive(Clone, PartialEq)]
+pub struct Entry {
+  contest: Rc<Contest>,
+  post: PostWithScores,
+  aggregator: Aggregator,
+  interval: Interval,
+  /// Judges in the contest.
+  judges: usize,
+}
+
+impl Entry {
+  fn matches(&self, filters: &FilterQuery) -> bool {
+    let Entry {
+      contest,
+      post,
+      aggregator,
+      ..
+    } = self;
+    let dimensions = &contest.config.dimensions;
+    filters.matches(
+      [post.post.title.as_str(), post.post.author.as_str()],
+      |dim| match dim {
+        Some(i) => Some(post.agg(i, *aggregator)),
+        None => Some(post.sum_agg(dimensions, *aggregator)),
+      },
+      dimensions,
+    )
+  }
+}
+
+impl TableEntryRenderer<Columns> for Entry {
   fn render_cell(&self, ctx: CellContext<Columns>) -> Cell {
+    let Entry {
+      contest,
+      post,
+      aggregator,
+      interval,
+      judges,
+    } = self;
+    let dimensions = &contest.config.dimensions;
     match ctx.column {
       Columns::Title => html!(
-        <Tooltip text={self.post.title.to_string()}>
-          <a href={self.post.url.clone()}>{ limit_string(&self.post.title, TITLE_LIMIT) }</a>
-        </Tooltip>
-      ),
-      Columns::Author => html! { &self.post.author },
-      Columns::LiteraryAvg => html!(
-        <Tooltip text={format!("Literary Average: {:.9}", self.literary_avg())}>
-          {format!("{:.3}", self.literary_avg())}
-        </Tooltip>
-      ),
-      Columns::LiteraryVar => html!(
-        <Tooltip text={format!("Literary Variance: {:.9}", self.literary_var())}>
-          {format!("{:.3}", self.literary_var())}
-        </Tooltip>
+        <>
+          <Tooltip text={post.post.title.to_string()}>
+            <a href={post.post.url.clone()}>{ limit_string(&post.post.title, TITLE_LIMIT) }</a>
+          </Tooltip>
+          if contest.changes.post(&post.post.id) {
+            <UpdatedLabel />
+          }
+        </>
       ),
-      Columns::ThinkingAvg => html!(
-        <Tooltip text={format!("Thinking Average: {:.9}", self.thinking_avg())}>
-          {format!("{:.3}", self.thinking_avg())}
-        </Tooltip>
-      ),
-      Columns::ThinkingVar => html!(
-        <Tooltip text={format!("Thinking Variance: {:.9}", self.thinking_var())}>
-          {format!("{:.3}", self.thinking_var())}
-        </Tooltip>
+      Columns::Author => html!(
+        <Link<AppRoute> target={AppRoute::contest(contest, ContestRoute::Author { name: post.post.author.clone() })}>
+          { &post.post.author }
+        </Link<AppRoute>>
       ),
-      Columns::MozhengAvg => html!(
-        <Tooltip text={format!("Mozheng Average: {:.9}", self.mozheng_avg())}>
-          {format!("{:.3}", self.mozheng_avg())}
+      Columns::Avg(i) => html!(
+        <Tooltip text={format!("{} {}: {:.9}", dimensions[i].name, aggregator.label(), post.agg(i, *aggregator))}>
+          {format!("{:.3}", post.agg(i, *aggregator))}
         </Tooltip>
       ),
-      Columns::MozhengVar => html!(
-        <Tooltip text={format!("Mozheng Variance: {:.9}", self.mozheng_var())}>
-          {format!("{:.3}", self.mozheng_var())}
+      Columns::Var(i) => html!(
+        <Tooltip text={format!("{} Variance: {:.9}", dimensions[i].name, post.var(i))}>
+          {format!("{:.3}", post.var(i))}
         </Tooltip>
       ),
       Columns::SumAvg => html!(
-        <Tooltip text={format!("Sum Average: {:.9}", self.sum_avg())}>
-          {format!("{:.3}", self.sum_avg())}
+        <Tooltip text={format!("Sum {}: {:.9}", aggregator.label(), post.sum_agg(dimensions, *aggregator))}>
+          {format!("{:.3}", post.sum_agg(dimensions, *aggregator))}
         </Tooltip>
       ),
       Columns::SumVar => html!(
-        <Tooltip text={format!("Sum Variance: {:.9}", self.sum_var())}>
-          {format!("{:.3}", self.sum_var())}
+        <Tooltip text={format!("Sum Variance: {:.9}", post.sum_var(dimensions))}>
+          {format!("{:.3}", post.sum_var(dimensions))}
         </Tooltip>
       ),
+      Columns::Coverage => {
+        let mut text = format!("Scored by {} of {} judges", post.judge_count(), judges);
+        match post.abstention_count() {
+          0 => {}
+          1 => text.push_str("; 1 abstained"),
+          n => text.push_str(&format!("; {} abstained", n)),
+        }
+        if post.excluded_count() > 0 {
+          text.push_str(&format!(
+            "; {} excluded for scoring their own post",
+            post.excluded_count()
+          ));
+        }
+        html!(
+          <Tooltip {text}>
+            {format!("{}/{}", post.judge_count(), judges)}
+          </Tooltip>
+        )
+      }
+      Columns::RankRange => {
+        let likely: Vec<_> = interval
+          .likely_ranks(3)
+          .into_iter()
+          .map(|(rank, p)| format!("#{}: {:.0}%", rank, p * 100.))
+          .collect();
+        html!(
+          <Tooltip text={format!("95% bootstrap interval; most likely {}", likely.join(", "))}>
+            {format!("{}–{}", interval.best_rank, interval.worst_rank)}
+          </Tooltip>
+        )
+      }
       Columns::Open => html!(
-        <Link<AppRoute> target={AppRoute::Post { id: self.id }}>{"Open"}</Link<AppRoute>>
+        <Link<AppRoute> target={AppRoute::contest(contest, ContestRoute::Post { id: post.post.id.clone() })}>{"Open"}</Link<AppRoute>>
       ),
     }
     .into()
   }
 }
 
+fn sort(entries: &mut [Entry], column: Columns, asc: bool, contest: &Contest) {
+  let dimensions = &contest.config.dimensions;
+  // Unscored posts have NaN aggregates and stay at the bottom.
+  let cmp = |a: f64, b: f64| order(a, b, asc);
+  let dir = |ord: Ordering| if asc { ord } else { ord.reverse() };
+
+  match column {
+    Columns::Title => {
+      entries.sort_by(|a, b| dir(a.post.post.title.cmp(&b.post.post.title)));
+    }
+    Columns::Author => {
+      entries.sort_by(|a, b| dir(a.post.post.author.cmp(&b.post.post.author)));
+    }
+    Columns::Avg(i) => {
+      entries.sort_by(|a, b| cmp(a.post.agg(i, a.aggregator), b.post.agg(i, b.aggregator)));
+    }
+    Columns::Var(i) => {
+      entries.sort_by(|a, b| cmp(a.post.var(i), b.post.var(i)));
+    }
+    Columns::SumAvg => {
+      entries.sort_by(|a, b| {
+        cmp(
+          a.post.sum_agg(dimensions, a.aggregator),
+          b.post.sum_agg(dimensions, b.aggregator),
+        )
+      });
+    }
+    Columns::SumVar => {
+      entries.sort_by(|a, b| cmp(a.post.sum_var(dimensions), b.post.sum_var(dimensions)));
+    }
+    Columns::Coverage => {
+      entries.sort_by(|a, b| dir(a.post.judge_count().cmp(&b.post.judge_count())));
+    }
+    Columns::RankRange => {
+      let range = |e: &Entry| (e.interval.best_rank, e.interval.worst_rank);
+      entries.sort_by(|a, b| dir(range(a).cmp(&range(b))));
+    }
+    _ => {}
+  };
+}
+
 #[function_component(Board)]
 pub fn board() -> Html {
-  let entries = use_state_eq(|| {
-    let mut p = POSTS_WITH_SCORES.clone();
-    p.sort_by(|a, b| a.post.title.cmp(&b.post.title));
-    p
+  let contest = use_contest();
+  let sort_by = use_state_eq(|| (Columns::Title, true));
+  let (query, onquery) = use_query::<BoardQuery>();
+  let aggregator = query.aggregator;
+  let intervals = use_memo((contest.clone(), aggregator), |(contest, aggregator)| {
+    contest.intervals(*aggregator)
   });
 
-  let on_sort_by = {
+  // Rebuilt whenever the contest is reloaded, keeping the chosen order.
+  let entries = use_state_eq(Vec::new);
+  {
     let entries = entries.clone();
+    use_effect_with(
+      (
+        contest.clone(),
+        *sort_by,
+        aggregator,
+        intervals,
+        query.filters.clone(),
+      ),
+      move |(contest, (column, asc), aggregator, intervals, filters)| {
+        let judges = judges(&contest.scores).len();
+        let mut p: Vec<_> = contest
+          .posts_with_scores
+          .iter()
+          .zip(intervals.iter())
+          .map(|(post, interval)| Entry {
+            contest: contest.clone(),
+            post: post.clone(),
+            aggregator: *aggregator,
+            interval: interval.clone(),
+            judges,
+          })
+          .filter(|e| e.matches(filters))
+          .collect();
+        sort(&mut p, *column, *asc, contest);
+        entries.set(p);
+      },
+    );
+  }
 
-    Some(Callback::from(move |val: TableHeaderSortBy<Columns>| {
-      let mut entries_sorted = (*entries).clone();
+  let on_sort_by = Some(use_callback(
+    sort_by.clone(),
+    |val: TableHeaderSortBy<Columns>, sort_by| sort_by.set((val.index, val.asc)),
+  ));
 
-      match val.index {
-        Columns::Title => {
-          entries_sorted.sort_by(|a, b| a.post.title.cmp(&b.post.title));
-        }
-        Columns::Author => {
-          entries_sorted.sort_by(|a, b| a.post.author.cmp(&b.post.author));
-        }
-        Columns::LiteraryAvg => {
-          entries_sorted.sort_by(|a, b| a.literary_avg().total_cmp(&b.literary_avg()));
-        }
-        Columns::LiteraryVar => {
-          entries_sorted.sort_by(|a, b| a.literary_var().total_cmp(&b.literary_var()));
-        }
-        Columns::ThinkingAvg => {
-          entries_sorted.sort_by(|a, b| a.thinking_avg().total_cmp(&b.thinking_avg()));
-        }
-        Columns::ThinkingVar => {
-          entries_sorted.sort_by(|a, b| a.thinking_var().total_cmp(&b.thinking_var()));
-        }
-        Columns::MozhengAvg => {
-          entries_sorted.sort_by(|a, b| a.mozheng_avg().total_cmp(&b.mozheng_avg()));
-        }
-        Columns::MozhengVar => {
-          entries_sorted.sort_by(|a, b| a.mozheng_var().total_cmp(&b.mozheng_var()));
-        }
-        Columns::SumAvg => {
-          entries_sorted.sort_by(|a, b| a.sum_avg().total_cmp(&b.sum_avg()));
-        }
-        Columns::SumVar => {
-          entries_sorted.sort_by(|a, b| a.sum_var().total_cmp(&b.sum_var()));
-        }
-        _ => {}
-      };
+  let (entries, _) = use_table_data(UseStateTableModel::new(entries));
 
-      if !val.asc {
-        entries_sorted.reverse();
-      }
-      entries.set(entries_sorted);
-    }))
+  let onaggregator = {
+    let query = query.clone();
+    onquery.reform(move |aggregator| BoardQuery {
+      aggregator,
+      ..query.clone()
+    })
   };
-
-  let (entries, _) = use_table_data(UseStateTableModel::new(entries));
+  let onfilters = onquery.reform(move |filters| BoardQuery {
+    aggregator,
+    filters,
+  });
 
   let header = html_nested! {
     <TableHeader<Columns>>
       <TableColumn<Columns> label="Title" index={Columns::Title} onsort={on_sort_by.clone()} />
       <TableColumn<Columns> label="Author" index={Columns::Author} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Lit. Avg" index={Columns::LiteraryAvg} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Lit. Var" index={Columns::LiteraryVar} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Thi. Avg" index={Columns::ThinkingAvg} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Thi. Var" index={Columns::ThinkingVar} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Moz. Avg" index={Columns::MozhengAvg} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Moz. Var" index={Columns::MozhengVar} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Σ Avg" index={Columns::SumAvg} onsort={on_sort_by.clone()} />
+      { for contest.config.dimensions.iter().enumerate().flat_map(|(i, d)| [
+        html_nested! {
+          <TableColumn<Columns> label={format!("{} {}", d.label, aggregator.label())} index={Columns::Avg(i)} onsort={on_sort_by.clone()} />
+        },
+        html_nested! {
+          <TableColumn<Columns> label={format!("{} Var", d.label)} index={Columns::Var(i)} onsort={on_sort_by.clone()} />
+        },
+      ]) }
+      <TableColumn<Columns> label={format!("Σ {}", aggregator.label())} index={Columns::SumAvg} onsort={on_sort_by.clone()} />
       <TableColumn<Columns> label="Σ Var" index={Columns::SumVar} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label="Judges" index={Columns::Coverage} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label="Rank range" index={Columns::RankRange} onsort={on_sort_by.clone()} />
       <TableColumn<Columns> label="" index={Columns::Open} />
     </TableHeader<Columns>>
   };
 
   html! (
-    <Table<Columns, UseTableData<Columns, UseStateTableModel<PostWithScores>>>
-      {header}
-      {entries}
-    />
+    <>
+      <FilterToolbar
+        query={query.filters.clone()}
+        onchange={onfilters}
+        placeholder="Search titles and authors"
+      >
+        <ToolbarItem>
+          <AggregatorSelect value={aggregator} onchange={onaggregator} />
+        </ToolbarItem>
+      </FilterToolbar>
+      <Table<Columns, UseTableData<Columns, UseStateTableModel<Entry>>>
+        {header}
+        {entries}
+      />
+    </>
   )
 }
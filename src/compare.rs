@@ -0,0 +1,333 @@
+use omlc_core::{aggregate, normalize_by_judge, ranks, Config, Method};
+use patternfly_yew::prelude::*;
+use plotters::{
+  prelude::*,
+  style::{
+    text_anchor::{HPos, Pos, VPos},
+    Color,
+  },
+};
+use plotters_canvas::CanvasBackend;
+use web_sys::{HtmlCanvasElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::{
+  app::use_contest,
+  model::{Contest, Post},
+  text::limit_chars,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+  pub post: Post,
+  pub avg: [f64; 2],
+  pub rank: [usize; 2],
+}
+
+impl Entry {
+  /// Places gained when switching from the first method to the second.
+  pub fn delta(&self) -> i64 {
+    self.rank[0] as i64 - self.rank[1] as i64
+  }
+}
+
+fn compute(contest: &Contest, methods: [Method; 2]) -> Result<Vec<Entry>, String> {
+  let mut sums = Vec::new();
+  for method in methods {
+    let config = Config {
+      normalizer: method,
+      ..contest.config.clone()
+    };
+    let scores = normalize_by_judge(&contest.scores, &config)
+      .map_err(|e| format!("{}: {}", method.name(), e))?;
+    let posts = aggregate(&contest.posts, &scores).map_err(|e| e.to_string())?;
+    sums.push(
+      posts
+        .iter()
+        .map(|p| p.sum_avg(&config.dimensions))
+        .collect::<Vec<_>>(),
+    );
+  }
+
+  let rank_l = ranks(&sums[0]);
+  let rank_r = ranks(&sums[1]);
+
+  Ok(
+    contest
+      .posts
+      .iter()
+      .enumerate()
+      .map(|(i, post)| Entry {
+        post: post.clone(),
+        avg: [sums[0][i], sums[1][i]],
+        rank: [rank_l[i], rank_r[i]],
+      })
+      .collect(),
+  )
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Columns {
+  Title,
+  Author,
+  LeftAvg,
+  LeftRank,
+  RightAvg,
+  RightRank,
+  Delta,
+}
+
+impl TableEntryRenderer<Columns> for Entry {
+  fn render_cell(&self, ctx: CellContext<Columns>) -> Cell {
+    match ctx.column {
+      Columns::Title => html!(<a href={self.post.url.clone()}>{ &self.post.title }</a>),
+      Columns::Author => html! { &self.post.author },
+      Columns::LeftAvg => html!(
+        <Tooltip text={format!("Sum Average: {:.9}", self.avg[0])}>
+          {format!("{:.3}", self.avg[0])}
+        </Tooltip>
+      ),
+      Columns::LeftRank => html! { self.rank[0] },
+      Columns::RightAvg => html!(
+        <Tooltip text={format!("Sum Average: {:.9}", self.avg[1])}>
+          {format!("{:.3}", self.avg[1])}
+        </Tooltip>
+      ),
+      Columns::RightRank => html! { self.rank[1] },
+      Columns::Delta => match self.delta() {
+        0 => html! { "–" },
+        d if d > 0 => html!(<span style="color: green">{ format!("▲ {}", d) }</span>),
+        d => html!(<span style="color: red">{ format!("▼ {}", -d) }</span>),
+      },
+    }
+    .into()
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+struct MethodSelectProps {
+  value: Method,
+  onchange: Callback<Method>,
+}
+
+#[function_component(MethodSelect)]
+fn method_select(MethodSelectProps { value, onchange }: &MethodSelectProps) -> Html {
+  let onchange = onchange.reform(|e: Event| {
+    let select: HtmlSelectElement = e.target_unchecked_into();
+    Method::from_name(&select.value()).unwrap_or_default()
+  });
+
+  html! {
+    <select class="pf-v5-c-form-control" {onchange}>
+      { for Method::ALL.iter().map(|m| html! {
+        <option value={m.name()} selected={m.name() == value.name()}>{ m.name() }</option>
+      }) }
+    </select>
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct BumpChartProps {
+  pub entries: Vec<Entry>,
+  pub labels: [String; 2],
+}
+
+#[function_component(BumpChart)]
+pub fn bump_chart(BumpChartProps { entries, labels }: &BumpChartProps) -> Html {
+  let canvas = use_node_ref();
+
+  {
+    let canvas = canvas.clone();
+
+    use_effect_with(
+      (canvas, entries.clone(), labels.clone()),
+      |(canvas, entries, labels)| {
+        const LABEL_LIMIT: usize = 16;
+
+        let element = canvas.cast::<HtmlCanvasElement>().unwrap();
+        let n = entries.len();
+
+        element.set_height((n * 18 + 60) as u32);
+        element.set_width(1000);
+
+        let root = CanvasBackend::with_canvas_object(element)
+          .unwrap()
+          .into_drawing_area();
+
+        root.fill(&WHITE).unwrap();
+
+        // Ranks are negated so that the first one is drawn on top.
+        let mut chart = ChartBuilder::on(&root)
+          .caption(format!("{} → {}", labels[0], labels[1]), ("sans-serif", 18))
+          .margin(5)
+          .build_cartesian_2d(-1f64..2f64, -(n as f64 + 0.5)..-0.5)
+          .unwrap();
+
+        let label = |e: &Entry| limit_chars(&e.post.title, LABEL_LIMIT);
+
+        chart
+          .draw_series(entries.iter().map(|e| {
+            let style = match e.delta() {
+              0 => BLACK.mix(0.3).stroke_width(1),
+              d if d > 0 => GREEN.stroke_width(2),
+              _ => RED.stroke_width(2),
+            };
+            PathElement::new(
+              vec![(0., -(e.rank[0] as f64)), (1., -(e.rank[1] as f64))],
+              style,
+            )
+          }))
+          .unwrap();
+
+        chart
+          .draw_series(
+            entries
+              .iter()
+              .flat_map(|e| [(0., -(e.rank[0] as f64)), (1., -(e.rank[1] as f64))])
+              .map(|p| Circle::new(p, 3, BLACK.filled())),
+          )
+          .unwrap();
+
+        let left = TextStyle::from(("sans-serif", 12)).pos(Pos::new(HPos::Right, VPos::Center));
+        let right = TextStyle::from(("sans-serif", 12)).pos(Pos::new(HPos::Left, VPos::Center));
+        chart
+          .draw_series(entries.iter().map(|e| {
+            Text::new(
+              format!("{} {}", label(e), e.rank[0]),
+              (-0.05, -(e.rank[0] as f64)),
+              left.clone(),
+            )
+          }))
+          .unwrap();
+        chart
+          .draw_series(entries.iter().map(|e| {
+            Text::new(
+              format!("{} {}", e.rank[1], label(e)),
+              (1.05, -(e.rank[1] as f64)),
+              right.clone(),
+            )
+          }))
+          .unwrap();
+
+        root.present().unwrap();
+      },
+    );
+  }
+
+  html! {
+    <canvas ref={canvas} style="width: 100%" />
+  }
+}
+
+#[function_component(Compare)]
+pub fn compare() -> Html {
+  let contest = use_contest();
+  let left = use_state_eq(Method::default);
+  let right = use_state_eq(|| Method::ZScore);
+
+  let result = use_memo((contest, *left, *right), |(contest, l, r)| {
+    compute(contest, [*l, *r])
+  });
+
+  let entries = use_state_eq(Vec::new);
+  {
+    let entries = entries.clone();
+    use_effect_with(result.clone(), move |result| {
+      let mut p = (**result).clone().unwrap_or_default();
+      p.sort_by_key(|e| e.rank[1]);
+      entries.set(p);
+    });
+  }
+
+  let on_sort_by = {
+    let entries = entries.clone();
+
+    Some(Callback::from(move |val: TableHeaderSortBy<Columns>| {
+      let mut entries_sorted = (*entries).clone();
+
+      match val.index {
+        Columns::Title => {
+          entries_sorted.sort_by(|a, b| a.post.title.cmp(&b.post.title));
+        }
+        Columns::Author => {
+          entries_sorted.sort_by(|a, b| a.post.author.cmp(&b.post.author));
+        }
+        Columns::LeftAvg => {
+          entries_sorted.sort_by(|a, b| a.avg[0].total_cmp(&b.avg[0]));
+        }
+        Columns::LeftRank => {
+          entries_sorted.sort_by_key(|e| e.rank[0]);
+        }
+        Columns::RightAvg => {
+          entries_sorted.sort_by(|a, b| a.avg[1].total_cmp(&b.avg[1]));
+        }
+        Columns::RightRank => {
+          entries_sorted.sort_by_key(|e| e.rank[1]);
+        }
+        Columns::Delta => {
+          entries_sorted.sort_by_key(|e| e.delta());
+        }
+      };
+
+      if !val.asc {
+        entries_sorted.reverse();
+      }
+      entries.set(entries_sorted);
+    }))
+  };
+
+  let chart_entries = (*entries).clone();
+  let (entries, _) = use_table_data(UseStateTableModel::new(entries));
+
+  let l = left.name();
+  let r = right.name();
+  let header = html_nested! {
+    <TableHeader<Columns>>
+      <TableColumn<Columns> label="Title" index={Columns::Title} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label="Author" index={Columns::Author} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label={format!("Σ Avg ({})", l)} index={Columns::LeftAvg} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label={format!("Rank ({})", l)} index={Columns::LeftRank} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label={format!("Σ Avg ({})", r)} index={Columns::RightAvg} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label={format!("Rank ({})", r)} index={Columns::RightRank} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label="Δ Rank" index={Columns::Delta} onsort={on_sort_by.clone()} />
+    </TableHeader<Columns>>
+  };
+
+  let onchange_left = use_callback(left.clone(), |m, left| left.set(m));
+  let onchange_right = use_callback(right.clone(), |m, right| right.set(m));
+
+  let selected = use_state_eq(|| 0);
+  let onselect = use_callback(selected.clone(), |index, selected| selected.set(index));
+
+  html! {
+    <>
+      <Toolbar>
+        <ToolbarContent>
+          <ToolbarItem>
+            <MethodSelect value={*left} onchange={onchange_left} />
+          </ToolbarItem>
+          <ToolbarItem>{ "vs." }</ToolbarItem>
+          <ToolbarItem>
+            <MethodSelect value={*right} onchange={onchange_right} />
+          </ToolbarItem>
+        </ToolbarContent>
+      </Toolbar>
+      if let Err(e) = &*result {
+        <Alert inline=true r#type={AlertType::Danger} title="Normalization failed">
+          { e.clone() }
+        </Alert>
+      }
+      <Tabs<usize> selected={*selected} {onselect}>
+        <Tab<usize> index=0 title="Table">
+          <Table<Columns, UseTableData<Columns, UseStateTableModel<Entry>>>
+            {header}
+            {entries}
+          />
+        </Tab<usize>>
+        <Tab<usize> index=1 title="Chart">
+          <BumpChart entries={chart_entries} labels={[l.to_string(), r.to_string()]} />
+        </Tab<usize>>
+      </Tabs<usize>>
+    </>
+  }
+}
@@ -0,0 +1,279 @@
+use std::{collections::HashMap, rc::Rc};
+
+use gloo::{
+  file::{Blob, ObjectUrl},
+  storage::{LocalStorage, Storage},
+};
+use omlc_core::write_judge_csv;
+use patternfly_yew::prelude::*;
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+use crate::{
+  app::use_contest,
+  model::{Contest, Score},
+};
+
+/// What a judge typed for one post, kept as text so that invalid input
+/// survives until it is fixed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct DraftRow {
+  /// One text per dimension.
+  values: Vec<String>,
+  comment: String,
+}
+
+impl DraftRow {
+  fn value(&self, i: usize) -> &str {
+    self.values.get(i).map_or("", String::as_str)
+  }
+}
+
+/// A judge's scores being entered, autosaved to local storage.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Draft {
+  owner: String,
+  /// By post id.
+  rows: HashMap<String, DraftRow>,
+}
+
+enum Action {
+  Owner(String),
+  Value {
+    post: String,
+    index: usize,
+    text: String,
+  },
+  Comment {
+    post: String,
+    text: String,
+  },
+}
+
+impl Reducible for Draft {
+  type Action = Action;
+
+  fn reduce(self: Rc<Self>, action: Action) -> Rc<Self> {
+    let mut draft = (*self).clone();
+    match action {
+      Action::Owner(owner) => draft.owner = owner,
+      Action::Value { post, index, text } => {
+        let row = draft.rows.entry(post).or_default();
+        if row.values.len() <= index {
+          row.values.resize(index + 1, String::new());
+        }
+        row.values[index] = text;
+      }
+      Action::Comment { post, text } => draft.rows.entry(post).or_default().comment = text,
+    }
+    Rc::new(draft)
+  }
+}
+
+/// Parses one raw score, which must lie within `0..=scale`. Empty input is
+/// not an error, merely missing.
+fn parse_value(text: &str, scale: f64) -> Result<Option<f64>, String> {
+  let text = text.trim();
+  if text.is_empty() {
+    return Ok(None);
+  }
+  match text.parse::<f64>() {
+    Ok(v) if (0. ..=scale).contains(&v) => Ok(Some(v)),
+    Ok(_) => Err(format!("`{}` is not within 0–{}", text, scale)),
+    Err(_) => Err(format!("`{}` is not a number", text)),
+  }
+}
+
+/// What a draft amounts to.
+struct Checked {
+  /// Every fully and validly scored post.
+  scores: Vec<Score>,
+  errors: Vec<String>,
+  /// Posts with at least one dimension left empty.
+  missing: usize,
+}
+
+fn check(draft: &Draft, contest: &Contest) -> Checked {
+  let dimensions = &contest.config.dimensions;
+  let owner = draft.owner.trim();
+  let raw_scales = contest.config.raw_scales(owner);
+  let mut checked = Checked {
+    scores: Vec::new(),
+    errors: Vec::new(),
+    missing: 0,
+  };
+
+  for (post_id, post) in contest.posts.iter().enumerate() {
+    let row = draft.rows.get(&post.id).cloned().unwrap_or_default();
+
+    let mut values = Vec::new();
+    for (i, d) in dimensions.iter().enumerate() {
+      match parse_value(row.value(i), raw_scales[i]) {
+        Ok(Some(v)) => values.push(Some(v)),
+        Ok(None) => {}
+        Err(e) => checked
+          .errors
+          .push(format!("{}, {}: {}", post.title, d.name, e)),
+      }
+    }
+
+    if values.len() < dimensions.len() {
+      checked.missing += 1;
+      continue;
+    }
+
+    let comment = row.comment.trim();
+    checked.scores.push(Score::new(
+      owner.to_string(),
+      post_id,
+      values,
+      raw_scales.clone(),
+      (!comment.is_empty()).then(|| comment.to_string()),
+    ));
+  }
+
+  checked
+}
+
+#[function_component(ScoreEntry)]
+pub fn score_entry() -> Html {
+  let contest = use_contest();
+  let key = format!("omlc-entry/{}", contest.slug);
+
+  let draft = {
+    let key = key.clone();
+    use_reducer_eq(move || LocalStorage::get::<Draft>(&key).unwrap_or_default())
+  };
+
+  use_effect_with((*draft).clone(), move |draft| {
+    // Losing the autosave is not worth interrupting the judge for.
+    let _ = LocalStorage::set(&key, draft);
+  });
+
+  let checked = check(&draft, &contest);
+  let owner = draft.owner.trim().to_string();
+  let ready = !owner.is_empty() && checked.errors.is_empty() && checked.missing == 0;
+
+  let url = use_memo(
+    (contest.clone(), (*draft).clone(), ready),
+    |(contest, draft, ready)| {
+      if !ready {
+        return None;
+      }
+      let checked = check(draft, contest);
+      let mut csv = Vec::new();
+      write_judge_csv(&mut csv, &contest.posts, &checked.scores, &contest.config).ok()?;
+      Some(ObjectUrl::from(Blob::new_with_options(
+        csv.as_slice(),
+        Some("text/csv"),
+      )))
+    },
+  );
+
+  let onowner = {
+    let draft = draft.dispatcher();
+    Callback::from(move |owner| draft.dispatch(Action::Owner(owner)))
+  };
+
+  let dimensions = &contest.config.dimensions;
+  // The judge's own scale once they are known by name.
+  let raw_scales = contest.config.raw_scales(&owner);
+  let rows = contest.posts.iter().map(|post| {
+    let row = draft.rows.get(&post.id).cloned().unwrap_or_default();
+
+    let values = raw_scales.iter().enumerate().map(|(index, &scale)| {
+      let text = row.value(index).to_string();
+      let state = match parse_value(&text, scale) {
+        Ok(_) => InputState::Default,
+        Err(_) => InputState::Error,
+      };
+      let onchange = {
+        let draft = draft.dispatcher();
+        let post = post.id.clone();
+        Callback::from(move |text| {
+          draft.dispatch(Action::Value {
+            post: post.clone(),
+            index,
+            text,
+          })
+        })
+      };
+      html! {
+        <td>
+          <TextInput value={text} {state} {onchange} r#type={TextInputType::Number} />
+        </td>
+      }
+    });
+
+    let oncomment = {
+      let draft = draft.dispatcher();
+      let post = post.id.clone();
+      Callback::from(move |text| {
+        draft.dispatch(Action::Comment {
+          post: post.clone(),
+          text,
+        })
+      })
+    };
+
+    html! {
+      <tr key={post.id.clone()}>
+        <td><a href={post.url.clone()}>{ &post.title }</a></td>
+        { for values }
+        <td><TextInput value={row.comment.clone()} onchange={oncomment} /></td>
+      </tr>
+    }
+  });
+
+  html! {
+    <>
+      <Toolbar>
+        <ToolbarContent>
+          <ToolbarItem>
+            <TextInput value={draft.owner.clone()} onchange={onowner} placeholder="Your name" />
+          </ToolbarItem>
+          <ToolbarItem>
+            if let Some(url) = &*url {
+              <a class="pf-v5-c-button pf-m-primary" href={url.to_string()} download={format!("{}.csv", owner)}>
+                { "Download CSV" }
+              </a>
+            } else {
+              <Button variant={ButtonVariant::Primary} disabled=true label="Download CSV" />
+            }
+          </ToolbarItem>
+        </ToolbarContent>
+      </Toolbar>
+      if owner.is_empty() {
+        <Alert inline=true r#type={AlertType::Info} title="Enter your name">
+          { "It names the downloaded CSV, which the organizers store as `<name>.csv`." }
+        </Alert>
+      }
+      if !checked.errors.is_empty() {
+        <Alert inline=true r#type={AlertType::Danger} title={format!("{} invalid scores", checked.errors.len())}>
+          <ul>
+            { for checked.errors.iter().map(|e| html! { <li>{ e }</li> }) }
+          </ul>
+        </Alert>
+      }
+      if checked.missing > 0 {
+        <Alert inline=true r#type={AlertType::Warning} title={format!("{} of {} posts still to score", checked.missing, contest.posts.len())}>
+          { "Every dimension of every post needs a score. Your input is saved in this browser meanwhile." }
+        </Alert>
+      }
+      <table class="pf-v5-c-table pf-m-compact" role="grid">
+        <thead>
+          <tr>
+            <th>{ "Post" }</th>
+            { for dimensions.iter().zip(&raw_scales).map(|(d, scale)| html! {
+              <th>{ format!("{} (0–{})", d.label, scale) }</th>
+            }) }
+            <th>{ "Comment" }</th>
+          </tr>
+        </thead>
+        <tbody>
+          { for rows }
+        </tbody>
+      </table>
+    </>
+  }
+}
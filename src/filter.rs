@@ -0,0 +1,304 @@
+use std::{fmt, str::FromStr};
+
+use omlc_core::Dimension;
+use patternfly_yew::prelude::*;
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::app::use_contest;
+
+/// Key of the weighted sum in range filters; dimensions go by their `key`.
+const SUM_KEY: &str = "sum";
+
+/// A range the values of a column must lie within; either bound may be open.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeFilter {
+  /// The key of a dimension, or `sum`.
+  pub column: String,
+  pub min: Option<f64>,
+  pub max: Option<f64>,
+}
+
+impl RangeFilter {
+  /// The dimension filtered on, or `None` for the weighted sum; `None` too
+  /// when the column is unknown.
+  fn target(&self, dimensions: &[Dimension]) -> Option<Option<usize>> {
+    if self.column == SUM_KEY {
+      Some(None)
+    } else {
+      dimensions
+        .iter()
+        .position(|d| d.key == self.column)
+        .map(Some)
+    }
+  }
+
+  /// Whether `value` lies within the range; a missing value never does.
+  pub fn contains(&self, value: Option<f64>) -> bool {
+    value.is_some_and(|v| {
+      self.min.map_or(true, |min| v >= min) && self.max.map_or(true, |max| v <= max)
+    })
+  }
+
+  /// The filter as shown on its chip, e.g. `Lit. 0.5–1` or `Σ ≥ 2`.
+  pub fn label(&self, dimensions: &[Dimension]) -> String {
+    let column = match self.target(dimensions) {
+      Some(Some(i)) => dimensions[i].label.clone(),
+      Some(None) => "Σ".to_string(),
+      None => self.column.clone(),
+    };
+    match (self.min, self.max) {
+      (Some(min), Some(max)) => format!("{} {}–{}", column, min, max),
+      (Some(min), None) => format!("{} ≥ {}", column, min),
+      (None, Some(max)) => format!("{} ≤ {}", column, max),
+      (None, None) => column,
+    }
+  }
+}
+
+/// Written `<column>:<min>..<max>`, e.g. `literary:0.5..1` or `sum:2..`.
+impl fmt::Display for RangeFilter {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let bound = |b: Option<f64>| b.map(|b| b.to_string()).unwrap_or_default();
+    write!(
+      f,
+      "{}:{}..{}",
+      self.column,
+      bound(self.min),
+      bound(self.max)
+    )
+  }
+}
+
+impl FromStr for RangeFilter {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (column, range) = s
+      .split_once(':')
+      .ok_or_else(|| format!("`{}` is not of the form <column>:<min>..<max>", s))?;
+    let (min, max) = range
+      .split_once("..")
+      .ok_or_else(|| format!("`{}` is not a range like 0.5..1", range))?;
+    let bound = |b: &str| match b.trim() {
+      "" => Ok(None),
+      b => b
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("`{}` is not a number", b)),
+    };
+    Ok(RangeFilter {
+      column: column.trim().to_string(),
+      min: bound(min)?,
+      max: bound(max)?,
+    })
+  }
+}
+
+/// Range filters, written comma separated in the URL.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Filters(pub Vec<RangeFilter>);
+
+impl Filters {
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+}
+
+impl From<Filters> for String {
+  fn from(filters: Filters) -> Self {
+    let filters: Vec<_> = filters.0.iter().map(|f| f.to_string()).collect();
+    filters.join(",")
+  }
+}
+
+impl TryFrom<String> for Filters {
+  type Error = String;
+
+  fn try_from(s: String) -> Result<Self, Self::Error> {
+    s.split(',')
+      .filter(|f| !f.trim().is_empty())
+      .map(str::parse)
+      .collect::<Result<_, _>>()
+      .map(Filters)
+  }
+}
+
+/// Search text and range filters of a table, kept in the URL next to the
+/// page's other settings.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilterQuery {
+  #[serde(default, skip_serializing_if = "String::is_empty")]
+  pub q: String,
+  #[serde(default, skip_serializing_if = "Filters::is_empty")]
+  pub filter: Filters,
+}
+
+impl FilterQuery {
+  /// Whether a row passes: one of `text` contains the search text, ignoring
+  /// case, and every filter holds for `value`, which picks the row's value
+  /// of a dimension or, for `None`, its weighted sum.
+  pub fn matches<'a>(
+    &self,
+    text: impl IntoIterator<Item = &'a str>,
+    value: impl Fn(Option<usize>) -> Option<f64>,
+    dimensions: &[Dimension],
+  ) -> bool {
+    let q = self.q.trim().to_lowercase();
+    let found = q.is_empty() || text.into_iter().any(|t| t.to_lowercase().contains(&q));
+    found
+      && self.filter.0.iter().all(|f| match f.target(dimensions) {
+        Some(target) => f.contains(value(target)),
+        // Filters on columns this contest lacks are ignored.
+        None => true,
+      })
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.q.trim().is_empty() && self.filter.is_empty()
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct FilterToolbarProps {
+  pub query: FilterQuery,
+  pub onchange: Callback<FilterQuery>,
+  /// What the search covers, e.g. `Search titles and authors`.
+  pub placeholder: String,
+  /// Further toolbar items, e.g. the page's selects.
+  #[prop_or_default]
+  pub children: Html,
+}
+
+/// Search input, range filter form and a chip per active filter.
+#[function_component(FilterToolbar)]
+pub fn filter_toolbar(
+  FilterToolbarProps {
+    query,
+    onchange,
+    placeholder,
+    children,
+  }: &FilterToolbarProps,
+) -> Html {
+  let contest = use_contest();
+  let dimensions = &contest.config.dimensions;
+
+  // The range being entered: column key, minimum and maximum as typed.
+  let column = use_state_eq(|| SUM_KEY.to_string());
+  let min = use_state_eq(String::new);
+  let max = use_state_eq(String::new);
+
+  let onsearch = {
+    let query = query.clone();
+    onchange.reform(move |q: String| FilterQuery { q, ..query.clone() })
+  };
+  let oncolumn = use_callback(column.clone(), |e: Event, column| {
+    let select: HtmlSelectElement = e.target_unchecked_into();
+    column.set(select.value());
+  });
+  let onmin = use_callback(min.clone(), |value: String, min| min.set(value));
+  let onmax = use_callback(max.clone(), |value: String, max| max.set(value));
+
+  let bound = |b: &str| b.trim().parse::<f64>().ok();
+  let range = RangeFilter {
+    column: (*column).clone(),
+    min: bound(&min),
+    max: bound(&max),
+  };
+  // A bound that does not parse leaves nothing to add.
+  let valid = (range.min.is_some() || range.max.is_some())
+    && (min.trim().is_empty() || range.min.is_some())
+    && (max.trim().is_empty() || range.max.is_some());
+
+  let onadd = {
+    let query = query.clone();
+    let (min, max) = (min.clone(), max.clone());
+    onchange.reform(move |_: MouseEvent| {
+      min.set(String::new());
+      max.set(String::new());
+      let mut query = query.clone();
+      query.filter.0.push(range.clone());
+      query
+    })
+  };
+  let onclear = onchange.reform(|_: MouseEvent| FilterQuery::default());
+
+  let columns: Vec<_> = dimensions
+    .iter()
+    .map(|d| (d.key.clone(), d.label.clone()))
+    .chain([(SUM_KEY.to_string(), "Σ".to_string())])
+    .collect();
+
+  let search_chip = (!query.q.trim().is_empty()).then(|| {
+    let onclose = {
+      let query = query.clone();
+      onchange.reform(move |_: ()| FilterQuery {
+        q: String::new(),
+        ..query.clone()
+      })
+    };
+    html! {
+      <Chip text={format!("“{}”", query.q.trim())} {onclose} />
+    }
+  });
+  let filter_chips = query.filter.0.iter().enumerate().map(|(i, f)| {
+    let onclose = {
+      let query = query.clone();
+      onchange.reform(move |_: ()| {
+        let mut query = query.clone();
+        query.filter.0.remove(i);
+        query
+      })
+    };
+    html! {
+      <Chip text={f.label(dimensions)} {onclose} />
+    }
+  });
+
+  html! {
+    <Toolbar>
+      <ToolbarContent>
+        <ToolbarItem>
+          <TextInput
+            value={query.q.clone()}
+            onchange={onsearch}
+            icon={TextInputIcon::Search}
+            placeholder={placeholder.clone()}
+          />
+        </ToolbarItem>
+        <ToolbarGroup>
+          <ToolbarItem>
+            <select class="pf-v5-c-form-control" onchange={oncolumn}>
+              { for columns.iter().map(|(key, label)| html! {
+                <option value={key.clone()} selected={key == &*column}>{ label }</option>
+              }) }
+            </select>
+          </ToolbarItem>
+          <ToolbarItem>
+            <TextInput value={(*min).clone()} onchange={onmin} r#type={TextInputType::Number} placeholder="Min" />
+          </ToolbarItem>
+          <ToolbarItem>
+            <TextInput value={(*max).clone()} onchange={onmax} r#type={TextInputType::Number} placeholder="Max" />
+          </ToolbarItem>
+          <ToolbarItem>
+            <Button variant={ButtonVariant::Secondary} disabled={!valid} label="Add filter" onclick={onadd} />
+          </ToolbarItem>
+        </ToolbarGroup>
+        { children.clone() }
+      </ToolbarContent>
+      if !query.is_empty() {
+        <ToolbarContent>
+          <ToolbarItem>
+            { for search_chip }
+            { for filter_chips }
+          </ToolbarItem>
+          <ToolbarItem>
+            <Button variant={ButtonVariant::Link} label="Clear all filters" onclick={onclear} />
+          </ToolbarItem>
+        </ToolbarContent>
+      }
+    </Toolbar>
+  }
+}
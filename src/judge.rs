@@ -0,0 +1,304 @@
+use std::rc::Rc;
+
+use omlc_core::{max_sum, mean, variance, Method};
+use patternfly_yew::prelude::*;
+use yew::prelude::*;
+use yew_nested_router::components::Link;
+
+use crate::{
+  app::{use_contest, AppRoute, ConflictLabel, ContestRoute, UpdatedLabel},
+  model::{Contest, Post, Score},
+  post::ScorePlot,
+  text::limit_chars,
+  values::{compare, missing_cell},
+};
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Columns {
+  Title,
+  Raw(usize),
+  Value(usize),
+  Sum,
+  Comment,
+}
+
+const TITLE_LIMIT: usize = 24;
+const COMMENT_LIMIT: usize = 32;
+
+/// One post as scored by the judge, before and after normalization.
+#[derive(Clone, PartialEq)]
+pub struct Entry {
+  contest: Rc<Contest>,
+  score: Score,
+}
+
+impl Entry {
+  fn post(&self) -> &Post {
+    &self.contest.posts[self.score.post_id]
+  }
+}
+
+impl TableEntryRenderer<Columns> for Entry {
+  fn render_cell(&self, ctx: CellContext<Columns>) -> Cell {
+    let Entry { contest, score } = self;
+    let dimensions = &contest.config.dimensions;
+    let post = self.post();
+    match ctx.column {
+      Columns::Title => html! {
+        <>
+          <Tooltip text={post.title.clone()}>
+            <Link<AppRoute> target={AppRoute::contest(contest, ContestRoute::Post { id: post.id.clone() })}>
+              { limit_chars(&post.title, TITLE_LIMIT) }
+            </Link<AppRoute>>
+          </Tooltip>
+          if contest.changes.score(&score.owner, &post.id) {
+            <UpdatedLabel />
+          }
+          if score.conflict {
+            <ConflictLabel score={score.clone()} />
+          }
+        </>
+      },
+      Columns::Raw(i) => match score.raw[i] {
+        Some(raw) => html! { raw.to_string() },
+        None => missing_cell(score, &dimensions[i].name, None),
+      },
+      Columns::Value(i) => match score.values[i] {
+        Some(value) => html!(
+          <Tooltip text={format!("{}: {:.9}", dimensions[i].name, value)}>
+            {format!("{:.3}", value)}
+          </Tooltip>
+        ),
+        None => missing_cell(score, &dimensions[i].name, score.raw[i]),
+      },
+      Columns::Sum => match score.sum(dimensions) {
+        Some(sum) => html!(
+          <Tooltip text={format!("Sum: {:.9}", sum)}>
+            {format!("{:.3}", sum)}
+          </Tooltip>
+        ),
+        None => missing_cell(score, "Sum", score.raw_sum(dimensions)),
+      },
+      Columns::Comment => html! {
+        limit_chars(score.comment.as_deref().unwrap_or_default(), COMMENT_LIMIT)
+      },
+    }
+    .into()
+  }
+
+  fn render_details(&self) -> Vec<Span> {
+    match &self.score.comment {
+      Some(comment) if comment.chars().count() > COMMENT_LIMIT => {
+        vec![Span::max(
+          html! { <p style="white-space: pre-wrap">{ comment }</p> },
+        )]
+      }
+      _ => vec![],
+    }
+  }
+}
+
+fn sort(entries: &mut [Entry], column: Columns, asc: bool, contest: &Contest) {
+  let dimensions = &contest.config.dimensions;
+
+  match column {
+    Columns::Title => {
+      entries.sort_by(|a, b| a.post().title.cmp(&b.post().title));
+    }
+    Columns::Raw(i) => {
+      entries.sort_by(|a, b| compare(a.score.raw[i], b.score.raw[i]));
+    }
+    Columns::Value(i) => {
+      entries.sort_by(|a, b| compare(a.score.values[i], b.score.values[i]));
+    }
+    Columns::Sum => {
+      entries.sort_by(|a, b| compare(a.score.sum(dimensions), b.score.sum(dimensions)));
+    }
+    Columns::Comment => {
+      entries.sort_by(|a, b| a.score.comment.cmp(&b.score.comment));
+    }
+  };
+
+  if !asc {
+    entries.reverse();
+  }
+}
+
+/// Every score of `owner`.
+fn judge_scores(contest: &Contest, owner: &str) -> Vec<Score> {
+  contest
+    .scores
+    .iter()
+    .filter(|s| s.owner == owner)
+    .cloned()
+    .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct JudgeProps {
+  pub owner: String,
+}
+
+#[function_component(Judge)]
+pub fn judge(JudgeProps { owner }: &JudgeProps) -> Html {
+  let contest = use_contest();
+  let scores = judge_scores(&contest, owner);
+  if scores.is_empty() {
+    return html! {
+      <EmptyState title="Judge not found" icon={Icon::Search}>
+        { format!("No scores by `{}` could be loaded.", owner) }
+      </EmptyState>
+    };
+  }
+  html! { <JudgeDetail owner={owner.clone()} {scores} /> }
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+struct JudgeDetailProps {
+  owner: String,
+  scores: Vec<Score>,
+}
+
+#[function_component(JudgeDetail)]
+fn judge_detail(JudgeDetailProps { owner, scores }: &JudgeDetailProps) -> Html {
+  let contest = use_contest();
+  let sort_by = use_state_eq(|| (Columns::Title, true));
+
+  // Rebuilt whenever the contest is reloaded, keeping the chosen order.
+  let entries = use_state_eq(Vec::new);
+  {
+    let entries = entries.clone();
+    use_effect_with(
+      (contest.clone(), scores.clone(), *sort_by),
+      move |(contest, scores, (column, asc))| {
+        let mut p: Vec<_> = scores
+          .iter()
+          .map(|score| Entry {
+            contest: contest.clone(),
+            score: score.clone(),
+          })
+          .collect();
+        sort(&mut p, *column, *asc, contest);
+        entries.set(p);
+      },
+    );
+  }
+
+  let on_sort_by = Some(use_callback(
+    sort_by.clone(),
+    |val: TableHeaderSortBy<Columns>, sort_by| sort_by.set((val.index, val.asc)),
+  ));
+
+  let (entries, onexpand) = use_table_data(UseStateTableModel::new(entries));
+
+  let dimensions = &contest.config.dimensions;
+
+  let header = html_nested! {
+    <TableHeader<Columns>>
+      <TableColumn<Columns> label="Post" index={Columns::Title} onsort={on_sort_by.clone()} />
+      { for dimensions.iter().enumerate().map(|(i, d)| html_nested! {
+        <TableColumn<Columns> label={format!("{} (raw)", d.label)} index={Columns::Raw(i)} onsort={on_sort_by.clone()} />
+      }) }
+      { for dimensions.iter().enumerate().map(|(i, d)| html_nested! {
+        <TableColumn<Columns> label={d.label.clone()} index={Columns::Value(i)} onsort={on_sort_by.clone()} />
+      }) }
+      <TableColumn<Columns> label="Σ" index={Columns::Sum} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label="Comment" index={Columns::Comment} onsort={on_sort_by.clone()} />
+    </TableHeader<Columns>>
+  };
+
+  // Excluded scores keep their raw values but are not normalized.
+  let raw_values = |i: usize| {
+    scores
+      .iter()
+      .filter(|s| !s.excluded)
+      .filter_map(move |s| s.raw[i])
+  };
+  let raw_scales = contest.config.raw_scales(owner);
+  let power = match contest.config.normalizer {
+    Method::Power(power) => Some(power),
+    _ => None,
+  };
+
+  let stats = dimensions.iter().enumerate().map(|(i, d)| {
+    let exponent = power.map(|power| match power.exponent(raw_values(i).collect()) {
+      Ok(Some(exponent)) => format!("{:.3}", exponent),
+      Ok(None) => "–".to_string(),
+      Err(e) => e.to_string(),
+    });
+    html! {
+      <tr>
+        <td>{ &d.name }</td>
+        <td>{ format!("{:.3}", mean(raw_values(i))) }</td>
+        <td>{ format!("{:.3}", variance(raw_values(i))) }</td>
+        if let Some(exponent) = exponent {
+          <td>{ exponent }</td>
+        }
+      </tr>
+    }
+  });
+
+  let s_sum: Vec<_> = scores.iter().filter_map(|s| s.sum(dimensions)).collect();
+  let abstained = scores.iter().filter(|s| s.abstained).count();
+  let excluded = scores.iter().filter(|s| s.excluded).count();
+  let scored = scores
+    .iter()
+    .filter(|s| !s.abstained && !s.excluded)
+    .count();
+
+  let selected = use_state_eq(|| 0);
+  let onselect = use_callback(selected.clone(), |index, selected| selected.set(index));
+
+  html! {
+    <>
+      <Title level={Level::H1}>
+        { owner }
+        <sub>
+          { format!("{} of {} posts scored", scored, contest.posts.len()) }
+          if abstained > 0 {
+            { format!(", {} abstained", abstained) }
+          }
+          if excluded > 0 {
+            { format!(", {} excluded", excluded) }
+          }
+        </sub>
+      </Title>
+      <table class="pf-v5-c-table pf-m-compact" role="grid">
+        <thead>
+          <tr>
+            <th>{ "Dimension" }</th>
+            <th>{ "Raw mean" }</th>
+            <th>{ "Raw variance" }</th>
+            if power.is_some() {
+              <th>{ "Power exponent" }</th>
+            }
+          </tr>
+        </thead>
+        <tbody>
+          { for stats }
+        </tbody>
+      </table>
+      <Tabs<usize> selected={*selected} {onselect}>
+        <Tab<usize> index=0 title="Detail">
+          <Table<Columns, UseTableData<Columns, UseStateTableModel<Entry>>>
+            mode={TableMode::Expandable}
+            {header}
+            {entries}
+            {onexpand}
+          />
+        </Tab<usize>>
+        { for dimensions.iter().enumerate().map(|(i, d)| html_nested! {
+          <Tab<usize> index={i + 1} title={d.name.clone()}>
+            <Title level={Level::H3}>{ "Raw" }</Title>
+            <ScorePlot scores={raw_values(i).collect::<Vec<_>>()} max_score={raw_scales[i]} />
+            <Title level={Level::H3}>{ "Normalized" }</Title>
+            <ScorePlot scores={scores.iter().filter_map(|s| s.values[i]).collect::<Vec<_>>()} max_score={1.0} />
+          </Tab<usize>>
+        }) }
+        <Tab<usize> index={dimensions.len() + 1} title="Sum">
+          <ScorePlot scores={s_sum} max_score={max_sum(dimensions)} />
+        </Tab<usize>>
+      </Tabs<usize>>
+    </>
+  }
+}
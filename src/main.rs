@@ -1,10 +1,19 @@
-#![feature(lazy_cell)]
-
+mod analysis;
 mod app;
+mod audit;
+mod author;
 mod board;
+mod compare;
+mod entry;
+mod filter;
+mod judge;
 mod model;
 mod post;
+mod problems;
+mod ranking;
 mod status;
+mod text;
+mod values;
 
 use crate::app::App;
 
@@ -1,260 +1,258 @@
-use std::sync::LazyLock;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::{bail, Context, Result};
-use csv::StringRecord;
+use gloo::net::http::Request;
 use include_dir::{include_dir, Dir};
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct Post {
-  pub title: String,
-  pub author: String,
-  pub url: String,
+use omlc_core::{
+  aggregate, audit_judge_csv, detect_outliers, load_posts, normalize, validate_judge_csv,
+  Aggregator, Config, Interval, JudgeAudit, Outliers, Problem,
+};
+use serde::Deserialize;
+
+pub use omlc_core::{Diagnostic, Post, PostWithScores, Score};
+
+/// Data compiled into the app, used when the served one cannot be fetched.
+static EMBEDDED: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/contests");
+
+/// An entry of `contests/contests.json`; the contest's data lives in
+/// `contests/<slug>/`.
+#[derive(Debug, Clone, Deserialize)]
+struct Entry {
+  slug: String,
+  name: String,
 }
 
-pub static POSTS: LazyLock<Vec<Post>> = LazyLock::new(|| {
-  serde_json::from_str(include_str!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/posts.json"
-  )))
-  .unwrap()
-});
+/// Judge resamples behind [`Contest::intervals`].
+pub const RESAMPLES: usize = 1000;
+/// Fixed so that intervals do not move between loads.
+const SEED: u64 = 0x6f6d_6c63;
+
+/// The files of a contest directory.
+struct Files {
+  config: Vec<u8>,
+  posts: Vec<u8>,
+  /// `(file name, contents)` of every judge's CSV.
+  scores: Vec<(String, Vec<u8>)>,
+}
 
+/// A contest with its scores loaded, normalized and aggregated.
 #[derive(Debug, Clone, PartialEq)]
-pub struct Score {
-  pub owner: String,
-  pub post_id: usize,
-  pub literary: f64,
-  pub thinking: f64,
-  pub mozheng: f64,
+pub struct Contest {
+  pub slug: String,
+  pub name: String,
+  pub config: Config,
+  pub posts: Vec<Post>,
+  /// Scores of every judge that could be normalized.
+  pub scores: Vec<Score>,
+  pub problems: Vec<Diagnostic>,
+  pub posts_with_scores: Vec<PostWithScores>,
+  pub outliers: Outliers,
+  /// Checks of the columns judges' spreadsheets derive, for every judge that
+  /// could be normalized.
+  pub audits: Vec<JudgeAudit>,
+  /// Scores changed by the last refresh.
+  pub changes: Changes,
 }
 
-impl Score {
-  fn parse_csv(owner: String, record: &StringRecord) -> Result<Self> {
-    let title = record[0].trim();
-    let post = POSTS
-      .iter()
-      .enumerate()
-      .filter(|(_, p)| p.title == title)
-      .next()
-      .with_context(|| format!("title `{}` not found", title))?;
-
-    let post_id = post.0;
-    let literary = record[1].to_string().parse()?;
-    let thinking = record[2].to_string().parse()?;
-    let mozheng = record[3].to_string().parse()?;
+impl Contest {
+  fn load(Entry { slug, name }: Entry, files: Files) -> Result<Self> {
+    let config: Config = serde_json::from_slice(&files.config)
+      .with_context(|| format!("in `{}/contest.json`", slug))?;
+    let posts =
+      load_posts(files.posts.as_slice()).with_context(|| format!("in `{}/posts.json`", slug))?;
+
+    let mut scores = Vec::new();
+    let mut problems = Vec::new();
+    let mut audits = Vec::new();
+
+    for (file, contents) in &files.scores {
+      let Some(owner) = file.strip_suffix(".csv") else {
+        continue;
+      };
+
+      let (mut judge_scores, diagnostics) =
+        validate_judge_csv(file, owner, contents.as_slice(), &posts, &config);
+      problems.extend(diagnostics);
+
+      if let Err(e) = normalize(&mut judge_scores, &config) {
+        problems.push(Diagnostic::file(
+          file,
+          Problem::Normalization {
+            message: e.to_string(),
+          },
+        ));
+        continue;
+      }
+      // Files the audit cannot read were already reported by the validation.
+      if let Ok(audit) = audit_judge_csv(owner, contents.as_slice(), &posts, &judge_scores, &config)
+      {
+        audits.push(audit);
+      }
+      scores.extend(judge_scores);
+    }
 
-    Ok(Score {
-      owner,
-      post_id,
-      literary,
-      thinking,
-      mozheng,
+    let posts_with_scores = aggregate(&posts, &scores)?;
+    let outliers = detect_outliers(&scores, &config);
+
+    Ok(Contest {
+      slug,
+      name,
+      config,
+      posts,
+      scores,
+      problems,
+      posts_with_scores,
+      outliers,
+      audits,
+      changes: Changes::default(),
     })
   }
 
-  fn scale_dimension(mut scores: Vec<f64>) -> Result<Vec<f64>> {
-    const EPS: f64 = 1e-6;
+  /// Bootstrap intervals of every post's Σ and rank, in the order of
+  /// `posts_with_scores`.
+  pub fn intervals(&self, aggregator: Aggregator) -> Vec<Interval> {
+    omlc_core::bootstrap(
+      &self.posts_with_scores,
+      &self.config.dimensions,
+      aggregator,
+      RESAMPLES,
+      SEED,
+    )
+  }
 
-    // Scale to [-1, 1]
-    let min = *scores
-      .iter()
-      .min_by(|a, b| a.partial_cmp(b).unwrap())
-      .unwrap();
-    let max = *scores
+  /// Why `score` or its judge were flagged as outliers, if they were.
+  pub fn outlier_reasons(&self, score: &Score) -> Vec<String> {
+    let dimensions = &self.config.dimensions;
+    let judge = self
+      .outliers
+      .judge(&score.owner)
+      .map(|o| format!("Judge {}", o.describe(dimensions)));
+    self
+      .outliers
+      .score(&score.owner, score.post_id)
+      .map(|o| o.describe())
+      .chain(judge)
+      .collect()
+  }
+
+  /// Every score, by owner and post id.
+  fn by_key(&self) -> HashMap<(&str, &str), &Score> {
+    self
+      .scores
       .iter()
-      .max_by(|a, b| a.partial_cmp(b).unwrap())
-      .unwrap();
+      .map(|s| ((s.owner.as_str(), self.posts[s.post_id].id.as_str()), s))
+      .collect()
+  }
+}
 
-    if min == max {
-      return Ok(scores.into_iter().map(|_| 0.).collect());
-    }
+/// Scores that were added, removed or modified between two loads of a contest.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Changes {
+  /// `(owner, post id)` of every changed score.
+  scores: HashSet<(String, String)>,
+}
 
-    scores = scores
-      .into_iter()
-      .map(|f| ((f - min) / (max - min) * 2. - 1.) * (2. / 3.))
+impl Changes {
+  pub fn between(old: &Contest, new: &Contest) -> Self {
+    let old = old.by_key();
+    let new = new.by_key();
+
+    let scores = old
+      .keys()
+      .chain(new.keys())
+      .filter(|key| {
+        let given = |s: &&Score| (s.raw.clone(), s.comment.clone(), s.abstained);
+        old.get(*key).map(given) != new.get(*key).map(given)
+      })
+      .map(|&(owner, post)| (owner.to_string(), post.to_string()))
       .collect();
-
-    // Ternary search
-    let mut l = 0.;
-    let mut r = 1.;
-    let mut res = 0.5;
-    while r - l > EPS {
-      let one_third = (r - l) / 3.;
-      let mid_l = l + one_third;
-      let mid_r = mid_l + one_third;
-
-      let res_l = check(scores.clone(), mid_l);
-      let res_r = check(scores.clone(), mid_r);
-
-      if res_l < res_r {
-        res = mid_l;
-        r = mid_r;
-      } else {
-        res = mid_r;
-        l = mid_l;
-      }
-    }
-
-    const MAX_ERROR: f64 = 1e-3;
-    let error = check(scores.clone(), res);
-
-    if error > MAX_ERROR {
-      bail!("Scaling failed: error {} is too large", error);
-    }
-
-    scores = scores.into_iter().map(|x| transform(x, res)).collect();
-    // console_dbg!(scores);
-    return Ok(scores);
-
-    fn transform(x: f64, t: f64) -> f64 {
-      let pow = -f64::ln(1. - t);
-      x.signum() * x.abs().powf(pow)
-    }
-
-    fn pow_avg(scores: &[f64]) -> f64 {
-      scores.iter().map(|s| s.powi(2)).sum::<f64>() / scores.len() as f64
-    }
-
-    fn check(scores: Vec<f64>, t: f64) -> f64 {
-      const TARGET_VARIANCE: f64 = 1. / 3.;
-      let transformed: Vec<_> = scores.into_iter().map(|x| transform(x, t)).collect();
-
-      (pow_avg(&transformed) - TARGET_VARIANCE).abs()
-    }
+    Changes { scores }
   }
 
-  fn scale(scores: &mut [Score]) -> Result<()> {
-    let mut s_literary: Vec<_> = scores.iter().map(|s| s.literary).collect();
-    let mut s_thinking: Vec<_> = scores.iter().map(|s| s.thinking).collect();
-    let mut s_mozheng: Vec<_> = scores.iter().map(|s| s.mozheng).collect();
-
-    s_literary = Self::scale_dimension(s_literary)?;
-    s_thinking = Self::scale_dimension(s_thinking)?;
-    s_mozheng = Self::scale_dimension(s_mozheng)?;
-
-    for i in 0..scores.len() {
-      scores[i].literary = s_literary[i];
-      scores[i].thinking = s_thinking[i];
-      scores[i].mozheng = s_mozheng[i];
-    }
-
-    Ok(())
+  pub fn score(&self, owner: &str, post_id: &str) -> bool {
+    self
+      .scores
+      .contains(&(owner.to_string(), post_id.to_string()))
   }
 
-  pub fn sum(&self) -> f64 {
-    self.literary + self.thinking + 1.5 * self.mozheng
+  pub fn post(&self, post_id: &str) -> bool {
+    self.scores.iter().any(|(_, id)| id == post_id)
   }
 }
 
-pub static SCORES: LazyLock<Vec<Score>> = LazyLock::new(|| {
-  static DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/scores");
-
-  DIR
-    .files()
-    .filter(|f| {
-      f.path()
-        .file_name()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .ends_with(".csv")
-    })
-    .map(|f| {
-      let owner = f.path().file_name().unwrap().to_str().unwrap();
-      let owner = owner[..owner.len() - 4].to_string();
-
-      let mut reader = csv::Reader::from_reader(f.contents());
+/// Every contest compiled into the app, in the order of
+/// `contests/contests.json`.
+pub fn embedded_contests() -> Result<Vec<Contest>> {
+  let file = |path: &str| {
+    EMBEDDED
+      .get_file(path)
+      .map(|f| f.contents().to_vec())
+      .with_context(|| format!("`{}` is missing", path))
+  };
+
+  let entries: Vec<Entry> = serde_json::from_slice(&file("contests.json")?)?;
+  entries
+    .into_iter()
+    .map(|entry| {
       let mut scores = Vec::new();
-
-      for res in reader.records() {
-        let record = res.unwrap();
-        scores.push(Score::parse_csv(owner.clone(), &record).unwrap());
+      if let Some(dir) = EMBEDDED.get_dir(format!("{}/scores", entry.slug)) {
+        for f in dir.files() {
+          let name = f.path().file_name().unwrap().to_string_lossy();
+          scores.push((name.into_owned(), f.contents().to_vec()));
+        }
       }
-
-      Score::scale(&mut scores).unwrap();
-      scores.into_iter()
+      let files = Files {
+        config: file(&format!("{}/contest.json", entry.slug))?,
+        posts: file(&format!("{}/posts.json", entry.slug))?,
+        scores,
+      };
+      Contest::load(entry, files)
     })
-    .flatten()
     .collect()
-});
-
-#[derive(Debug, Clone, PartialEq)]
-pub struct PostWithScores {
-  pub id: usize,
-  pub post: Post,
-  pub scores: Vec<Score>,
 }
 
-impl PostWithScores {
-  pub fn literary_avg(&self) -> f64 {
-    let sum: f64 = self.scores.iter().map(|s| s.literary).sum();
-    let n = self.scores.len();
-    sum / (n as f64)
-  }
-
-  pub fn thinking_avg(&self) -> f64 {
-    let sum: f64 = self.scores.iter().map(|s| s.thinking).sum();
-    let n = self.scores.len();
-    sum / (n as f64)
-  }
-
-  pub fn mozheng_avg(&self) -> f64 {
-    let sum: f64 = self.scores.iter().map(|s| s.mozheng).sum();
-    let n = self.scores.len();
-    sum / (n as f64)
-  }
-
-  pub fn sum_avg(&self) -> f64 {
-    let sum: f64 = self.scores.iter().map(|s| s.sum()).sum();
-    let n = self.scores.len();
-    sum / (n as f64)
-  }
-
-  pub fn literary_var(&self) -> f64 {
-    let avg = self.literary_avg();
-    let sum: f64 = self.scores.iter().map(|s| (s.literary - avg).powi(2)).sum();
-    let n = self.scores.len();
-    sum / (n as f64)
-  }
-
-  pub fn thinking_var(&self) -> f64 {
-    let avg = self.thinking_avg();
-    let sum: f64 = self.scores.iter().map(|s| (s.thinking - avg).powi(2)).sum();
-    let n = self.scores.len();
-    sum / (n as f64)
-  }
-
-  pub fn mozheng_var(&self) -> f64 {
-    let avg = self.mozheng_avg();
-    let sum: f64 = self.scores.iter().map(|s| (s.mozheng - avg).powi(2)).sum();
-    let n = self.scores.len();
-    sum / (n as f64)
-  }
-
-  pub fn sum_var(&self) -> f64 {
-    let avg = self.sum_avg();
-    let sum: f64 = self.scores.iter().map(|s| (s.sum() - avg).powi(2)).sum();
-    let n = self.scores.len();
-    sum / (n as f64)
+async fn fetch(path: &str) -> Result<Vec<u8>> {
+  let url = format!("contests/{}", path);
+  let response = Request::get(&url)
+    .send()
+    .await
+    .with_context(|| format!("cannot fetch `{}`", url))?;
+  if !response.ok() {
+    bail!(
+      "cannot fetch `{}`: {} {}",
+      url,
+      response.status(),
+      response.status_text()
+    );
   }
+  Ok(response.binary().await?)
 }
 
-pub static POSTS_WITH_SCORES: LazyLock<Vec<PostWithScores>> = LazyLock::new(|| {
-  let mut res: Vec<_> = POSTS
-    .iter()
-    .cloned()
-    .enumerate()
-    .map(|(id, post)| PostWithScores {
-      id,
-      post,
-      scores: Vec::new(),
-    })
-    .collect();
+/// Every contest served next to the app. Each contest lists its judges' CSVs
+/// in `scores/index.json`.
+pub async fn fetch_contests() -> Result<Vec<Contest>> {
+  let entries: Vec<Entry> = serde_json::from_slice(&fetch("contests.json").await?)?;
+
+  let mut res = Vec::new();
+  for entry in entries {
+    let slug = &entry.slug;
+    let manifest: Vec<String> =
+      serde_json::from_slice(&fetch(&format!("{}/scores/index.json", slug)).await?)
+        .with_context(|| format!("in `{}/scores/index.json`", slug))?;
+
+    let mut scores = Vec::new();
+    for file in manifest {
+      let contents = fetch(&format!("{}/scores/{}", slug, file)).await?;
+      scores.push((file, contents));
+    }
 
-  for score in SCORES.iter() {
-    res[score.post_id].scores.push(score.clone());
+    let files = Files {
+      config: fetch(&format!("{}/contest.json", slug)).await?,
+      posts: fetch(&format!("{}/posts.json", slug)).await?,
+      scores,
+    };
+    res.push(Contest::load(entry, files)?);
   }
 
-  res
-});
+  Ok(res)
+}
@@ -1,48 +1,109 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, rc::Rc};
 
+use omlc_core::max_sum;
 use patternfly_yew::prelude::*;
 use plotters::{prelude::*, style::Color};
 use plotters_canvas::CanvasBackend;
 use web_sys::HtmlCanvasElement;
 use yew::prelude::*;
+use yew_nested_router::components::Link;
 
-use crate::model::{PostWithScores, Score, POSTS_WITH_SCORES};
+use crate::{
+  app::{
+    use_contest, use_query, AppRoute, ConflictLabel, ContestRoute, OutlierLabel, UpdatedLabel,
+  },
+  filter::{FilterQuery, FilterToolbar},
+  model::{Contest, PostWithScores, Score},
+  text::limit_chars,
+  values::{compare, ValueView, ValueViewSelect, ValuesQuery},
+};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum Columns {
   Owner,
-  Literary,
-  Thinking,
-  Mozheng,
+  Value(usize),
   Sum,
+  Comment,
 }
 
-impl TableEntryRenderer<Columns> for Score {
+const COMMENT_LIMIT: usize = 32;
+
+#[derive(Clone, PartialEq)]
+pub struct Entry {
+  contest: Rc<Contest>,
+  score: Score,
+  view: ValueView,
+}
+
+impl Entry {
+  fn matches(&self, filters: &FilterQuery) -> bool {
+    let Entry {
+      contest,
+      score,
+      view,
+    } = self;
+    let dimensions = &contest.config.dimensions;
+    let text = [score.owner.as_str()]
+      .into_iter()
+      .chain(score.comment.as_deref());
+    filters.matches(
+      text,
+      |dim| match dim {
+        Some(i) => view.value(score, i),
+        None => view.sum(score, dimensions),
+      },
+      dimensions,
+    )
+  }
+}
+
+impl TableEntryRenderer<Columns> for Entry {
   fn render_cell(&self, ctx: CellContext<Columns>) -> Cell {
+    let Entry {
+      contest,
+      score,
+      view,
+    } = self;
+    let dimensions = &contest.config.dimensions;
     match ctx.column {
-      Columns::Owner => html! { &self.owner },
-      Columns::Literary => html!(
-        <Tooltip text={format!("Literary: {:.9}", self.literary)}>
-          {format!("{:.3}", self.literary)}
-        </Tooltip>
-      ),
-      Columns::Thinking => html!(
-        <Tooltip text={format!("Thinking: {:.9}", self.thinking)}>
-          {format!("{:.3}", self.thinking)}
-        </Tooltip>
-      ),
-      Columns::Mozheng => html!(
-        <Tooltip text={format!("Mozheng: {:.9}", self.mozheng)}>
-          {format!("{:.3}", self.mozheng)}
-        </Tooltip>
-      ),
-      Columns::Sum => html!(
-        <Tooltip text={format!("Sum: {:.9}", self.sum())}>
-          {format!("{:.3}", self.sum())}
-        </Tooltip>),
+      Columns::Owner => {
+        let reasons = contest.outlier_reasons(score);
+        html! {
+          <>
+            <Link<AppRoute> target={AppRoute::contest(contest, ContestRoute::Judge { owner: score.owner.clone() })}>
+              { &score.owner }
+            </Link<AppRoute>>
+            if contest.changes.score(&score.owner, &contest.posts[score.post_id].id) {
+              <UpdatedLabel />
+            }
+            if score.conflict {
+              <ConflictLabel score={score.clone()} />
+            }
+            if !reasons.is_empty() {
+              <OutlierLabel {reasons} />
+            }
+          </>
+        }
+      }
+      Columns::Value(i) => view.value_cell(score, i, dimensions),
+      Columns::Sum => view.sum_cell(score, dimensions),
+      Columns::Comment => html! {
+        limit_chars(score.comment.as_deref().unwrap_or_default(), COMMENT_LIMIT)
+      },
     }
     .into()
   }
+
+  fn render_details(&self) -> Vec<Span> {
+    match &self.score.comment {
+      Some(comment) if comment.chars().count() > COMMENT_LIMIT => {
+        vec![Span::max(
+          html! { <p style="white-space: pre-wrap">{ comment }</p> },
+        )]
+      }
+      _ => vec![],
+    }
+  }
 }
 
 #[derive(Clone, Debug, PartialEq, Properties)]
@@ -74,7 +135,10 @@ pub fn score_plot(ScorePlotProps { scores, max_score }: &ScorePlotProps) -> Html
     use_effect_with(
       (canvas, data, max_score.clone()),
       |(canvas, data, max_score)| {
-        let element = canvas.cast::<HtmlCanvasElement>().unwrap();
+        // Absent when there is nothing to plot.
+        let Some(element) = canvas.cast::<HtmlCanvasElement>() else {
+          return;
+        };
 
         element.set_height(400);
         element.set_width(1000);
@@ -129,6 +193,13 @@ pub fn score_plot(ScorePlotProps { scores, max_score }: &ScorePlotProps) -> Html
     );
   }
 
+  if scores.is_empty() {
+    return html! {
+      <EmptyState title="No scores" icon={Icon::InfoCircle}>
+        { "No judge gave a value here." }
+      </EmptyState>
+    };
+  }
   html! {
     <canvas ref={canvas} style="width: 100%" />
   }
@@ -136,98 +207,169 @@ pub fn score_plot(ScorePlotProps { scores, max_score }: &ScorePlotProps) -> Html
 
 #[derive(Clone, Debug, PartialEq, Properties)]
 pub struct PostProps {
-  pub post_id: usize,
+  pub post_id: String,
 }
 
 #[function_component(Post)]
 pub fn post(PostProps { post_id }: &PostProps) -> Html {
+  let contest = use_contest();
+  match contest
+    .posts_with_scores
+    .iter()
+    .find(|p| &p.post.id == post_id)
+  {
+    Some(post) => html! { <PostDetail post={post.clone()} /> },
+    None => html! {
+      <EmptyState title="Post not found" icon={Icon::Search}>
+        { format!("No post has the id `{}`.", post_id) }
+      </EmptyState>
+    },
+  }
+}
+
+fn sort(entries: &mut [Entry], column: Columns, asc: bool, contest: &Contest) {
+  let dimensions = &contest.config.dimensions;
+
+  match column {
+    Columns::Owner => {
+      entries.sort_by(|a, b| a.score.owner.cmp(&b.score.owner));
+    }
+    Columns::Value(i) => {
+      entries.sort_by(|a, b| compare(a.view.value(&a.score, i), b.view.value(&b.score, i)));
+    }
+    Columns::Sum => {
+      entries.sort_by(|a, b| {
+        let sum = |e: &Entry| e.view.sum(&e.score, dimensions);
+        compare(sum(a), sum(b))
+      });
+    }
+    Columns::Comment => {
+      entries.sort_by(|a, b| a.score.comment.cmp(&b.score.comment));
+    }
+  };
+
+  if !asc {
+    entries.reverse();
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+struct PostDetailProps {
+  post: PostWithScores,
+}
+
+#[function_component(PostDetail)]
+fn post_detail(PostDetailProps { post }: &PostDetailProps) -> Html {
+  let contest = use_contest();
   let PostWithScores {
     id: _,
     post,
     scores,
-  } = POSTS_WITH_SCORES[*post_id].clone();
+  } = post.clone();
 
-  let entries = use_state_eq(|| {
-    let mut p = scores.clone();
-    p.sort_by(|a, b| a.owner.cmp(&b.owner));
-    p
-  });
+  let sort_by = use_state_eq(|| (Columns::Owner, true));
+  let (query, onquery) = use_query::<ValuesQuery>();
+  let view = query.values;
 
-  let on_sort_by = {
+  // Rebuilt whenever the contest is reloaded, keeping the chosen order.
+  let entries = use_state_eq(Vec::new);
+  {
     let entries = entries.clone();
+    use_effect_with(
+      (
+        contest.clone(),
+        scores.clone(),
+        *sort_by,
+        query.filters.clone(),
+        view,
+      ),
+      move |(contest, scores, (column, asc), filters, view)| {
+        let mut p: Vec<_> = scores
+          .iter()
+          .map(|score| Entry {
+            contest: contest.clone(),
+            score: score.clone(),
+            view: *view,
+          })
+          .filter(|e| e.matches(filters))
+          .collect();
+        sort(&mut p, *column, *asc, contest);
+        entries.set(p);
+      },
+    );
+  }
 
-    Some(Callback::from(move |val: TableHeaderSortBy<Columns>| {
-      let mut entries_sorted = (*entries).clone();
-
-      match val.index {
-        Columns::Owner => {
-          entries_sorted.sort_by(|a, b| a.owner.cmp(&b.owner));
-        }
-        Columns::Literary => {
-          entries_sorted.sort_by(|a, b| a.literary.partial_cmp(&b.literary).unwrap());
-        }
-        Columns::Thinking => {
-          entries_sorted.sort_by(|a, b| a.thinking.partial_cmp(&b.thinking).unwrap());
-        }
-        Columns::Mozheng => {
-          entries_sorted.sort_by(|a, b| a.mozheng.partial_cmp(&b.mozheng).unwrap());
-        }
-        Columns::Sum => {
-          entries_sorted.sort_by(|a, b| a.sum().partial_cmp(&b.sum()).unwrap());
-        }
-      };
-
-      if !val.asc {
-        entries_sorted.reverse();
-      }
-      entries.set(entries_sorted);
-    }))
-  };
+  let on_sort_by = Some(use_callback(
+    sort_by.clone(),
+    |val: TableHeaderSortBy<Columns>, sort_by| sort_by.set((val.index, val.asc)),
+  ));
 
-  let (entries, _) = use_table_data(UseStateTableModel::new(entries));
+  let (entries, onexpand) = use_table_data(UseStateTableModel::new(entries));
 
   let header = html_nested! {
     <TableHeader<Columns>>
       <TableColumn<Columns> label="Owner" index={Columns::Owner} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Lit." index={Columns::Literary} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Thi." index={Columns::Thinking} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Moz." index={Columns::Mozheng} onsort={on_sort_by.clone()} />
+      { for contest.config.dimensions.iter().enumerate().map(|(i, d)| html_nested! {
+        <TableColumn<Columns> label={d.label.clone()} index={Columns::Value(i)} onsort={on_sort_by.clone()} />
+      }) }
       <TableColumn<Columns> label="Σ" index={Columns::Sum} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label="Comment" index={Columns::Comment} onsort={on_sort_by.clone()} />
     </TableHeader<Columns>>
   };
 
-  let s_literary: Vec<_> = scores.iter().map(|s| s.literary).collect();
-  let s_thinking: Vec<_> = scores.iter().map(|s| s.thinking).collect();
-  let s_mozheng: Vec<_> = scores.iter().map(|s| s.mozheng).collect();
-  let s_sum: Vec<_> = scores.iter().map(|s| s.sum()).collect();
+  let dimensions = &contest.config.dimensions;
+  let s_sum: Vec<_> = scores.iter().filter_map(|s| s.sum(dimensions)).collect();
 
   let selected = use_state_eq(|| 0);
   let onselect = use_callback(selected.clone(), |index, selected| selected.set(index));
+  let onview = {
+    let query = query.clone();
+    onquery.reform(move |values| ValuesQuery {
+      values,
+      ..query.clone()
+    })
+  };
+  let onfilters = onquery.reform(move |filters| ValuesQuery {
+    values: view,
+    filters,
+  });
 
   html! {
     <>
       <Title level={Level::H1}>
         <a href={post.url.clone()}>{&post.title}</a>
-        <sub>{format!("by {}", post.author)}</sub>
+        <sub>
+          { "by " }
+          <Link<AppRoute> target={AppRoute::contest(&contest, ContestRoute::Author { name: post.author.clone() })}>
+            { &post.author }
+          </Link<AppRoute>>
+        </sub>
       </Title>
       <Tabs<usize> selected={*selected} {onselect}>
         <Tab<usize> index=0 title="Detail">
-          <Table<Columns, UseTableData<Columns, UseStateTableModel<Score>>>
+          <FilterToolbar
+            query={query.filters.clone()}
+            onchange={onfilters}
+            placeholder="Search judges and comments"
+          >
+            <ToolbarItem>
+              <ValueViewSelect value={view} onchange={onview} />
+            </ToolbarItem>
+          </FilterToolbar>
+          <Table<Columns, UseTableData<Columns, UseStateTableModel<Entry>>>
+            mode={TableMode::Expandable}
             {header}
             {entries}
+            {onexpand}
           />
         </Tab<usize>>
-        <Tab<usize> index=1 title="Literary">
-          <ScorePlot scores={s_literary} max_score={1.0}/>
-        </Tab<usize>>
-        <Tab<usize> index=2 title="Thinking">
-          <ScorePlot scores={s_thinking} max_score={1.0}/>
-        </Tab<usize>>
-        <Tab<usize> index=3 title="Mozheng">
-          <ScorePlot scores={s_mozheng} max_score={1.0}/>
-        </Tab<usize>>
-        <Tab<usize> index=4 title="Sum">
-          <ScorePlot scores={s_sum} max_score={3.5}/>
+        { for dimensions.iter().enumerate().map(|(i, d)| html_nested! {
+          <Tab<usize> index={i + 1} title={d.name.clone()}>
+            <ScorePlot scores={scores.iter().filter_map(|s| s.values[i]).collect::<Vec<_>>()} max_score={1.0}/>
+          </Tab<usize>>
+        }) }
+        <Tab<usize> index={dimensions.len() + 1} title="Sum">
+          <ScorePlot scores={s_sum} max_score={max_sum(dimensions)}/>
         </Tab<usize>>
       </Tabs<usize>>
     </>
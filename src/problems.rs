@@ -0,0 +1,71 @@
+use patternfly_yew::prelude::*;
+use yew::prelude::*;
+
+use crate::{app::use_contest, model::Diagnostic};
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Columns {
+  File,
+  Line,
+  Column,
+  Problem,
+}
+
+impl TableEntryRenderer<Columns> for Diagnostic {
+  fn render_cell(&self, ctx: CellContext<Columns>) -> Cell {
+    match ctx.column {
+      Columns::File => html! { &self.file },
+      Columns::Line => html! { self.line.map(|l| l.to_string()).unwrap_or_default() },
+      Columns::Column => html! { self.column.map(|c| c.to_string()).unwrap_or_default() },
+      Columns::Problem => html! { self.problem.to_string() },
+    }
+    .into()
+  }
+}
+
+#[function_component(Problems)]
+pub fn problems() -> Html {
+  let contest = use_contest();
+  let problems = &contest.problems;
+
+  let entries = use_state_eq(Vec::new);
+  {
+    let entries = entries.clone();
+    use_effect_with(problems.clone(), move |problems| {
+      let mut p = problems.clone();
+      p.sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
+      entries.set(p);
+    });
+  }
+
+  let (entries, _) = use_table_data(UseStateTableModel::new(entries));
+
+  if problems.is_empty() {
+    return html! {
+      <EmptyState title="No problems" icon={Icon::CheckCircle}>
+        { "Every row of every judge's CSV was loaded." }
+      </EmptyState>
+    };
+  }
+
+  let header = html_nested! {
+    <TableHeader<Columns>>
+      <TableColumn<Columns> label="File" index={Columns::File} />
+      <TableColumn<Columns> label="Line" index={Columns::Line} />
+      <TableColumn<Columns> label="Column" index={Columns::Column} />
+      <TableColumn<Columns> label="Problem" index={Columns::Problem} />
+    </TableHeader<Columns>>
+  };
+
+  html! (
+    <>
+      <Alert inline=true r#type={AlertType::Warning} title={format!("{} problems found", problems.len())}>
+        { "The affected rows are left out of every ranking." }
+      </Alert>
+      <Table<Columns, UseTableData<Columns, UseStateTableModel<Diagnostic>>>
+        {header}
+        {entries}
+      />
+    </>
+  )
+}
@@ -0,0 +1,199 @@
+use omlc_core::Interval;
+use patternfly_yew::prelude::*;
+use plotters::{
+  prelude::*,
+  style::{
+    text_anchor::{HPos, Pos, VPos},
+    Color,
+  },
+};
+use plotters_canvas::CanvasBackend;
+use web_sys::HtmlCanvasElement;
+use yew::prelude::*;
+
+use crate::{
+  app::{use_contest, use_query},
+  board::{AggregatorSelect, BoardQuery},
+  model::RESAMPLES,
+  text::limit_chars,
+};
+
+/// A post on the ranking chart.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bar {
+  pub title: String,
+  pub value: f64,
+  pub interval: Interval,
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct RankingChartProps {
+  /// Best first.
+  pub bars: Vec<Bar>,
+}
+
+#[function_component(RankingChart)]
+pub fn ranking_chart(RankingChartProps { bars }: &RankingChartProps) -> Html {
+  let canvas = use_node_ref();
+
+  {
+    let canvas = canvas.clone();
+
+    use_effect_with((canvas, bars.clone()), |(canvas, bars)| {
+      const LABEL_LIMIT: usize = 16;
+
+      let element = canvas.cast::<HtmlCanvasElement>().unwrap();
+      let n = bars.len();
+
+      element.set_height((n * 18 + 60) as u32);
+      element.set_width(1000);
+
+      let root = CanvasBackend::with_canvas_object(element)
+        .unwrap()
+        .into_drawing_area();
+
+      root.fill(&WHITE).unwrap();
+
+      let finite = |v: f64| v.is_finite().then_some(v);
+      let min = bars
+        .iter()
+        .filter_map(|b| finite(b.interval.low))
+        .fold(f64::INFINITY, f64::min);
+      let max = bars
+        .iter()
+        .filter_map(|b| finite(b.interval.high))
+        .fold(f64::NEG_INFINITY, f64::max);
+      if min > max {
+        return;
+      }
+      // The left third holds the titles, the right edge the rank ranges.
+      let width = max - min;
+      let (left, right) = (min - width / 2., max + width / 8.);
+
+      // Positions are negated so that the first post is drawn on top.
+      let mut chart = ChartBuilder::on(&root)
+        .caption("Σ with 95% bootstrap intervals", ("sans-serif", 18))
+        .margin(5)
+        .x_label_area_size(30)
+        .build_cartesian_2d(left..right, -(n as f64 + 0.5)..-0.5)
+        .unwrap();
+
+      chart
+        .configure_mesh()
+        .disable_y_mesh()
+        .disable_y_axis()
+        .bold_line_style(&WHITE.mix(0.3))
+        .x_desc("Σ")
+        .draw()
+        .unwrap();
+
+      chart
+        .draw_series(
+          bars
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.interval.low.is_finite())
+            .map(|(i, b)| {
+              ErrorBar::new_horizontal(
+                -(i as f64 + 1.),
+                b.interval.low,
+                b.value,
+                b.interval.high,
+                BLACK.filled(),
+                6,
+              )
+            }),
+        )
+        .unwrap();
+
+      let label = |b: &Bar| limit_chars(&b.title, LABEL_LIMIT);
+
+      let style = TextStyle::from(("sans-serif", 12)).pos(Pos::new(HPos::Right, VPos::Center));
+      chart
+        .draw_series(bars.iter().enumerate().map(|(i, b)| {
+          Text::new(
+            format!("{}. {}", i + 1, label(b)),
+            (min - width / 20., -(i as f64 + 1.)),
+            style.clone(),
+          )
+        }))
+        .unwrap();
+
+      chart
+        .draw_series(bars.iter().enumerate().map(|(i, b)| {
+          Text::new(
+            format!("#{}–{}", b.interval.best_rank, b.interval.worst_rank),
+            (right, -(i as f64 + 1.)),
+            style.clone(),
+          )
+        }))
+        .unwrap();
+
+      root.present().unwrap();
+    });
+  }
+
+  html! {
+    <canvas ref={canvas} style="width: 100%" />
+  }
+}
+
+#[function_component(Ranking)]
+pub fn ranking() -> Html {
+  let contest = use_contest();
+  let (query, onquery) = use_query::<BoardQuery>();
+  let aggregator = query.aggregator;
+
+  let bars = use_memo((contest.clone(), aggregator), |(contest, aggregator)| {
+    let dimensions = &contest.config.dimensions;
+    let mut bars: Vec<_> = contest
+      .posts_with_scores
+      .iter()
+      .zip(contest.intervals(*aggregator))
+      .map(|(post, interval)| Bar {
+        title: post.post.title.clone(),
+        value: post.sum_agg(dimensions, *aggregator),
+        interval,
+      })
+      .collect();
+    // Posts nobody scored go last.
+    let key = |b: &Bar| {
+      if b.value.is_nan() {
+        f64::NEG_INFINITY
+      } else {
+        b.value
+      }
+    };
+    bars.sort_by(|a, b| key(b).total_cmp(&key(a)));
+    bars
+  });
+
+  let onaggregator = {
+    let query = query.clone();
+    onquery.reform(move |aggregator| BoardQuery {
+      aggregator,
+      ..query.clone()
+    })
+  };
+
+  html! {
+    <>
+      <Toolbar>
+        <ToolbarContent>
+          <ToolbarItem>
+            <AggregatorSelect value={aggregator} onchange={onaggregator} />
+          </ToolbarItem>
+        </ToolbarContent>
+      </Toolbar>
+      <p>
+        { format!(
+          "Judges are drawn with replacement {} times; each post's Σ is aggregated over the \
+           drawn judges and all posts are ranked again. Bars span the middle 95% of Σ, the \
+           right column the middle 95% of ranks.",
+          RESAMPLES
+        ) }
+      </p>
+      <RankingChart bars={(*bars).clone()} />
+    </>
+  }
+}
@@ -1,103 +1,178 @@
+use std::rc::Rc;
+
 use patternfly_yew::prelude::*;
 use yew::prelude::*;
+use yew_nested_router::components::Link;
 
-use crate::model::{Score, POSTS, SCORES};
+use crate::{
+  app::{
+    use_contest, use_query, AppRoute, ConflictLabel, ContestRoute, OutlierLabel, UpdatedLabel,
+  },
+  filter::{FilterQuery, FilterToolbar},
+  model::{Contest, Score},
+  text::limit_chars,
+  values::{compare, ValueView, ValueViewSelect, ValuesQuery},
+};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum Columns {
   PostTitle,
   Owner,
-  Literary,
-  Thinking,
-  Mozheng,
+  Value(usize),
   Sum,
+  Comment,
 }
 
-fn limit_string(s: &str, limit: usize) -> String {
-  if s.len() > limit {
-    format!("{}…", String::from_utf8_lossy(&s.as_bytes()[..limit - 2]))
-  } else {
-    s.to_string()
-  }
+const TITLE_LIMIT: usize = 24;
+const COMMENT_LIMIT: usize = 24;
+
+#[derive(Clone, PartialEq)]
+pub struct Entry {
+  contest: Rc<Contest>,
+  score: Score,
+  view: ValueView,
 }
 
-const TITLE_LIMIT: usize = 24;
+impl Entry {
+  fn title(&self) -> &str {
+    &self.contest.posts[self.score.post_id].title
+  }
 
-impl TableEntryRenderer<Columns> for Score {
+  fn matches(&self, filters: &FilterQuery) -> bool {
+    let Entry {
+      contest,
+      score,
+      view,
+    } = self;
+    let dimensions = &contest.config.dimensions;
+    let post = &contest.posts[score.post_id];
+    let text = [&post.title, &post.author, &score.owner]
+      .into_iter()
+      .map(String::as_str)
+      .chain(score.comment.as_deref());
+    filters.matches(
+      text,
+      |dim| match dim {
+        Some(i) => view.value(score, i),
+        None => view.sum(score, dimensions),
+      },
+      dimensions,
+    )
+  }
+}
+
+impl TableEntryRenderer<Columns> for Entry {
   fn render_cell(&self, ctx: CellContext<Columns>) -> Cell {
+    let Entry {
+      contest,
+      score,
+      view,
+    } = self;
+    let dimensions = &contest.config.dimensions;
     match ctx.column {
       Columns::PostTitle => html!(
-        <Tooltip text={POSTS[self.post_id].title.clone()}>
-          { limit_string(&POSTS[self.post_id].title, TITLE_LIMIT) }
-        </Tooltip>
-      ),
-      Columns::Owner => html! { &self.owner },
-      Columns::Literary => html!(
-        <Tooltip text={format!("Literary: {:.9}", self.literary)}>
-          {format!("{:.3}", self.literary)}
-        </Tooltip>
-      ),
-      Columns::Thinking => html!(
-        <Tooltip text={format!("Thinking: {:.9}", self.thinking)}>
-          {format!("{:.3}", self.thinking)}
-        </Tooltip>
-      ),
-      Columns::Mozheng => html!(
-        <Tooltip text={format!("Mozheng: {:.9}", self.mozheng)}>
-          {format!("{:.3}", self.mozheng)}
-        </Tooltip>
-      ),
-      Columns::Sum => html!(
-        <Tooltip text={format!("Sum: {:.9}", self.sum())}>
-          {format!("{:.3}", self.sum())}
+        <Tooltip text={self.title().to_string()}>
+          { limit_chars(self.title(), TITLE_LIMIT) }
         </Tooltip>
       ),
+      Columns::Owner => {
+        let reasons = contest.outlier_reasons(score);
+        html! {
+          <>
+            <Link<AppRoute> target={AppRoute::contest(contest, ContestRoute::Judge { owner: score.owner.clone() })}>
+              { &score.owner }
+            </Link<AppRoute>>
+            if contest.changes.score(&score.owner, &contest.posts[score.post_id].id) {
+              <UpdatedLabel />
+            }
+            if score.conflict {
+              <ConflictLabel score={score.clone()} />
+            }
+            if !reasons.is_empty() {
+              <OutlierLabel {reasons} />
+            }
+          </>
+        }
+      }
+      Columns::Value(i) => view.value_cell(score, i, dimensions),
+      Columns::Sum => view.sum_cell(score, dimensions),
+      Columns::Comment => match &score.comment {
+        Some(comment) => html!(
+          <Tooltip text={comment.clone()}>
+            { limit_chars(comment, COMMENT_LIMIT) }
+          </Tooltip>
+        ),
+        None => html!(),
+      },
     }
     .into()
   }
 }
 
+fn sort(entries: &mut [Entry], column: Columns, asc: bool, contest: &Contest) {
+  let dimensions = &contest.config.dimensions;
+
+  match column {
+    Columns::PostTitle => {
+      entries.sort_by(|a, b| a.title().cmp(b.title()));
+    }
+    Columns::Owner => {
+      entries.sort_by(|a, b| a.score.owner.cmp(&b.score.owner));
+    }
+    Columns::Value(i) => {
+      entries.sort_by(|a, b| compare(a.view.value(&a.score, i), b.view.value(&b.score, i)));
+    }
+    Columns::Sum => {
+      entries.sort_by(|a, b| {
+        let sum = |e: &Entry| e.view.sum(&e.score, dimensions);
+        compare(sum(a), sum(b))
+      });
+    }
+    Columns::Comment => {
+      entries.sort_by(|a, b| a.score.comment.cmp(&b.score.comment));
+    }
+  };
+
+  if !asc {
+    entries.reverse();
+  }
+}
+
 #[function_component(Status)]
 pub fn status() -> Html {
-  let entries = use_state_eq(|| {
-    let mut p = SCORES.clone();
-    p.sort_by(|a, b| POSTS[a.post_id].title.cmp(&POSTS[b.post_id].title));
-    p
-  });
-
-  let on_sort_by = {
-    let entries = entries.clone();
+  let contest = use_contest();
+  let sort_by = use_state_eq(|| (Columns::PostTitle, true));
 
-    Some(Callback::from(move |val: TableHeaderSortBy<Columns>| {
-      let mut entries_sorted = (*entries).clone();
+  let (query, onquery) = use_query::<ValuesQuery>();
+  let view = query.values;
 
-      match val.index {
-        Columns::PostTitle => {
-          entries_sorted.sort_by(|a, b| POSTS[a.post_id].title.cmp(&POSTS[b.post_id].title));
-        }
-        Columns::Owner => {
-          entries_sorted.sort_by(|a, b| a.owner.cmp(&b.owner));
-        }
-        Columns::Literary => {
-          entries_sorted.sort_by(|a, b| a.literary.partial_cmp(&b.literary).unwrap());
-        }
-        Columns::Thinking => {
-          entries_sorted.sort_by(|a, b| a.thinking.partial_cmp(&b.thinking).unwrap());
-        }
-        Columns::Mozheng => {
-          entries_sorted.sort_by(|a, b| a.mozheng.partial_cmp(&b.mozheng).unwrap());
-        }
-        Columns::Sum => {
-          entries_sorted.sort_by(|a, b| a.sum().partial_cmp(&b.sum()).unwrap());
-        }
-      };
+  // Rebuilt whenever the contest is reloaded, keeping the chosen order.
+  let entries = use_state_eq(Vec::new);
+  {
+    let entries = entries.clone();
+    use_effect_with(
+      (contest.clone(), *sort_by, query.filters.clone(), view),
+      move |(contest, (column, asc), filters, view)| {
+        let mut p: Vec<_> = contest
+          .scores
+          .iter()
+          .map(|score| Entry {
+            contest: contest.clone(),
+            score: score.clone(),
+            view: *view,
+          })
+          .filter(|e| e.matches(filters))
+          .collect();
+        sort(&mut p, *column, *asc, contest);
+        entries.set(p);
+      },
+    );
+  }
 
-      if !val.asc {
-        entries_sorted.reverse();
-      }
-      entries.set(entries_sorted);
-    }))
-  };
+  let on_sort_by = Some(use_callback(
+    sort_by.clone(),
+    |val: TableHeaderSortBy<Columns>, sort_by| sort_by.set((val.index, val.asc)),
+  ));
 
   let (entries, _) = use_table_data(UseStateTableModel::new(entries));
 
@@ -105,17 +180,41 @@ pub fn status() -> Html {
     <TableHeader<Columns>>
       <TableColumn<Columns> label="Post" index={Columns::PostTitle} onsort={on_sort_by.clone()} />
       <TableColumn<Columns> label="Owner" index={Columns::Owner} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Lit." index={Columns::Literary} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Thi." index={Columns::Thinking} onsort={on_sort_by.clone()} />
-      <TableColumn<Columns> label="Moz." index={Columns::Mozheng} onsort={on_sort_by.clone()} />
+      { for contest.config.dimensions.iter().enumerate().map(|(i, d)| html_nested! {
+        <TableColumn<Columns> label={d.label.clone()} index={Columns::Value(i)} onsort={on_sort_by.clone()} />
+      }) }
       <TableColumn<Columns> label="Σ" index={Columns::Sum} onsort={on_sort_by.clone()} />
+      <TableColumn<Columns> label="Comment" index={Columns::Comment} onsort={on_sort_by.clone()} />
     </TableHeader<Columns>>
   };
 
+  let onview = {
+    let query = query.clone();
+    onquery.reform(move |values| ValuesQuery {
+      values,
+      ..query.clone()
+    })
+  };
+  let onfilters = onquery.reform(move |filters| ValuesQuery {
+    values: view,
+    filters,
+  });
+
   html! (
-    <Table<Columns, UseTableData<Columns, UseStateTableModel<Score>>>
-      {header}
-      {entries}
-    />
+    <>
+      <FilterToolbar
+        query={query.filters.clone()}
+        onchange={onfilters}
+        placeholder="Search posts, authors, judges and comments"
+      >
+        <ToolbarItem>
+          <ValueViewSelect value={view} onchange={onview} />
+        </ToolbarItem>
+      </FilterToolbar>
+      <Table<Columns, UseTableData<Columns, UseStateTableModel<Entry>>>
+        {header}
+        {entries}
+      />
+    </>
   )
 }
@@ -0,0 +1,8 @@
+/// Shortens `s` to at most `limit` characters, marking the cut with `…`.
+pub fn limit_chars(s: &str, limit: usize) -> String {
+  if s.chars().count() > limit {
+    format!("{}…", s.chars().take(limit - 1).collect::<String>())
+  } else {
+    s.to_string()
+  }
+}
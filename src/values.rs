@@ -0,0 +1,167 @@
+use std::cmp::Ordering;
+
+use omlc_core::Dimension;
+use patternfly_yew::prelude::*;
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::{filter::FilterQuery, model::Score};
+
+/// Which values of the scores a table shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueView {
+  #[default]
+  Normalized,
+  Raw,
+  Both,
+}
+
+impl ValueView {
+  pub const ALL: [ValueView; 3] = [ValueView::Normalized, ValueView::Raw, ValueView::Both];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      ValueView::Normalized => "Normalized",
+      ValueView::Raw => "Raw",
+      ValueView::Both => "Raw → normalized",
+    }
+  }
+
+  fn key(&self) -> &'static str {
+    match self {
+      ValueView::Normalized => "normalized",
+      ValueView::Raw => "raw",
+      ValueView::Both => "both",
+    }
+  }
+
+  /// The value of dimension `i` tables sort by; the normalized one unless
+  /// only raw values are shown.
+  pub fn value(&self, score: &Score, i: usize) -> Option<f64> {
+    match self {
+      ValueView::Raw => score.raw[i],
+      _ => score.values[i],
+    }
+  }
+
+  /// The weighted sum tables sort by.
+  pub fn sum(&self, score: &Score, dimensions: &[Dimension]) -> Option<f64> {
+    match self {
+      ValueView::Raw => score.raw_sum(dimensions),
+      _ => score.sum(dimensions),
+    }
+  }
+
+  /// The cell of dimension `i`, with every value in its tooltip.
+  pub fn value_cell(&self, score: &Score, i: usize, dimensions: &[Dimension]) -> Html {
+    let (Some(raw), scale, Some(value)) = (score.raw[i], score.raw_scale[i], score.values[i])
+    else {
+      return missing_cell(score, &dimensions[i].name, score.raw[i]);
+    };
+    let text = format!(
+      "{}: {} of {} raw, {:.9} normalized",
+      dimensions[i].name, raw, scale, value
+    );
+    let shown = match self {
+      ValueView::Normalized => format!("{:.3}", value),
+      ValueView::Raw => format!("{}/{}", raw, scale),
+      ValueView::Both => format!("{}/{} → {:.3}", raw, scale, value),
+    };
+    html! {
+      <Tooltip {text}>{ shown }</Tooltip>
+    }
+  }
+
+  /// The cell of the weighted sum, with both sums in its tooltip.
+  pub fn sum_cell(&self, score: &Score, dimensions: &[Dimension]) -> Html {
+    let (Some(raw), Some(sum)) = (score.raw_sum(dimensions), score.sum(dimensions)) else {
+      return missing_cell(score, "Sum", score.raw_sum(dimensions));
+    };
+    let text = format!("Sum: {} raw, {:.9} normalized", raw, sum);
+    let shown = match self {
+      ValueView::Normalized => format!("{:.3}", sum),
+      ValueView::Raw => raw.to_string(),
+      ValueView::Both => format!("{} → {:.3}", raw, sum),
+    };
+    html! {
+      <Tooltip {text}>{ shown }</Tooltip>
+    }
+  }
+}
+
+/// The cell of a value the judge did not give, or that is excluded; `raw`
+/// is then struck through.
+pub fn missing_cell(score: &Score, name: &str, raw: Option<f64>) -> Html {
+  if score.excluded {
+    let text = format!(
+      "{} wrote this post, so their scores of it are excluded",
+      score.owner
+    );
+    return html! {
+      <Tooltip {text}>
+        if let Some(raw) = raw {
+          <s>{ raw.to_string() }</s>
+        } else {
+          { "excluded" }
+        }
+      </Tooltip>
+    };
+  }
+
+  let (text, shown) = if score.abstained {
+    (
+      format!("{} abstained from scoring this post", score.owner),
+      "abstained",
+    )
+  } else {
+    (format!("{}: not scored", name), "–")
+  };
+  html! {
+    <Tooltip {text}>{ shown }</Tooltip>
+  }
+}
+
+/// Orders values with the missing ones first, so that they end up last when
+/// sorting in descending order.
+pub fn compare(a: Option<f64>, b: Option<f64>) -> Ordering {
+  match (a, b) {
+    (Some(a), Some(b)) => a.total_cmp(&b),
+    _ => a.is_some().cmp(&b.is_some()),
+  }
+}
+
+/// Score table settings kept in the URL.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValuesQuery {
+  #[serde(default)]
+  pub values: ValueView,
+  #[serde(flatten)]
+  pub filters: FilterQuery,
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct ValueViewSelectProps {
+  pub value: ValueView,
+  pub onchange: Callback<ValueView>,
+}
+
+#[function_component(ValueViewSelect)]
+pub fn value_view_select(ValueViewSelectProps { value, onchange }: &ValueViewSelectProps) -> Html {
+  let onchange = onchange.reform(|e: Event| {
+    let select: HtmlSelectElement = e.target_unchecked_into();
+    ValueView::ALL
+      .into_iter()
+      .find(|v| v.key() == select.value())
+      .unwrap_or_default()
+  });
+
+  html! {
+    <select class="pf-v5-c-form-control" {onchange}>
+      { for ValueView::ALL.iter().map(|v| html! {
+        <option value={v.key()} selected={v == value}>{ v.name() }</option>
+      }) }
+    </select>
+  }
+}